@@ -1,5 +1,7 @@
 use clap::{Args, Parser, Subcommand};
 
+use crate::utils::options::Security;
+
 #[derive(Subcommand, Debug)]
 pub enum ProverEntity {
     #[clap(about = "Generate a proof from a given trace of a cairo program execution")]
@@ -12,14 +14,28 @@ pub enum ProverEntity {
 pub struct ProveArgs {
     pub trace_bin_path: String,
     pub memory_bin_path: String,
+    pub air_public_input_path: String,
     pub proof_path: String,
     pub output_path: String,
+    /// Security level targeted by the proof
+    #[clap(long, value_enum, default_value_t = Security::Conjecturable100)]
+    pub security: Security,
+    /// Blowup factor of the trace, a power of two from 2 to 64. Larger ones give smaller
+    /// proofs, faster to verify but slower to generate. Picked for the security level by default.
+    #[clap(long)]
+    pub blowup_factor: Option<u8>,
 }
 
 #[derive(Args, Debug)]
 pub struct VerifyArgs {
+    /// Hex encoded pedersen hash of the compiled program, as given by `cairo-hash-program`
+    pub program_hash: String,
     pub proof_path: String,
+    /// Reject proofs targeting a lower security level
+    #[clap(long, value_enum, default_value_t = Security::Conjecturable100)]
+    pub min_security: Security,
 }
+
 #[derive(Parser, Debug)]
 pub struct ProverArgs {
     #[clap(subcommand)]
@@ -1,4 +1,8 @@
-use utils::{prove, error::VerifierError};
+use clap::ValueEnum;
+use hyle_contract::HyleOutput;
+use hyle_verifier_core::{extra_input_str, to_json_output, Verifier, VerifyError};
+use utils::{prove, verify_proof_bytes, error::VerifierError};
+use utils::options::{CairoProofOptions, Security};
 use wasm_bindgen::prelude::*;
 
 pub mod utils;
@@ -13,11 +17,43 @@ macro_rules! log {
     }
 }
 
+/// `security` (e.g. "conjecturable100") and `blowup_factor` default to the options of the CLI.
 #[wasm_bindgen]
-pub fn wasm_prove(trace_data: Vec<u8>, memory_data: Vec<u8>, output: &str) -> Result<JsValue, VerifierError> {
+pub fn wasm_prove(trace_data: Vec<u8>, memory_data: Vec<u8>, air_public_input: &str, output: &str, security: Option<String>, blowup_factor: Option<u8>) -> Result<JsValue, VerifierError> {
     // Sets up panic for easy debugging
     std::panic::set_hook(Box::new(console_error_panic_hook::hook));
 
-    let proof = prove(trace_data, memory_data, output)?;
+    let mut options = CairoProofOptions::default();
+    if let Some(security) = security {
+        options.security = Security::from_str(&security, true).map_err(VerifierError)?;
+    }
+    options.blowup_factor = blowup_factor;
+    let proof = prove(trace_data, memory_data, air_public_input, output, options)?;
     Ok(serde_wasm_bindgen::to_value(&proof).unwrap())
-}
\ No newline at end of file
+}
+
+pub struct CairoVerifier;
+
+impl Verifier for CairoVerifier {
+    fn name(&self) -> &'static str {
+        "cairo"
+    }
+
+    /// `program_id` is the hex encoded program hash, `proof` a proof written by `prove`.
+    /// Proofs targeting a lower security level than `extra_inputs.min_security` are rejected,
+    /// it defaults to the one of the CLI.
+    fn verify(
+        &self,
+        program_id: &str,
+        proof: &[u8],
+        extra_inputs: &serde_json::Value,
+    ) -> Result<HyleOutput<serde_json::Value>, VerifyError> {
+        let min_security = match extra_input_str(extra_inputs, "min_security")? {
+            Some(security) => Security::from_str(security, true).map_err(|err| {
+                VerifyError::MalformedProof(format!("Invalid min_security: {}", err))
+            })?,
+            None => CairoProofOptions::default().security,
+        };
+        to_json_output(verify_proof_bytes(program_id, proof, min_security)?)
+    }
+}
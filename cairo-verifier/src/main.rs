@@ -2,6 +2,7 @@ use std::fs;
 
 use clap::Parser;
 use crate::utils::error::VerifierError;
+use crate::utils::options::CairoProofOptions;
 
 mod commands;
 mod utils;
@@ -14,29 +15,28 @@ struct Cli {
 fn main() -> Result<(), VerifierError> {
     let args: commands::ProverArgs = commands::ProverArgs::parse();
 
-    let res = match args.entity {
-        commands::ProverEntity::Verify(args) => {   
-            utils::verify_proof(&args.proof_path)
+    let output = match args.entity {
+        commands::ProverEntity::Verify(args) => {
+            // Verification errors are printed as JSON, with one exit code per kind of error
+            utils::verify_proof(&args.program_hash, &args.proof_path, args.min_security).unwrap_or_else(|err| err.exit())
         },
         commands::ProverEntity::Prove(args) => {
             let program_output_str: String = fs::read_to_string(&args.output_path).expect("Failed to read output file");
 
             let trace_data = fs::read(&args.trace_bin_path).expect("failed to load trace file");
             let memory_data = fs::read(&args.memory_bin_path).expect("failed to load memory file");
+            let air_public_input = fs::read_to_string(&args.air_public_input_path).expect("failed to load air public input file");
             let proof = utils::prove(
                 trace_data,
                 memory_data,
-                &program_output_str
+                &air_public_input,
+                &program_output_str,
+                CairoProofOptions { security: args.security, blowup_factor: args.blowup_factor },
             )?;
             std::fs::write(&args.proof_path, proof)?;
-            Ok(format!("Proof written to {}", &args.proof_path))
-        }
-    };
-    match res {
-        Result::Ok(output) => println!("{}", output),
-        Result::Err(err) => {
-            return Err(err);
+            format!("Proof written to {}", &args.proof_path)
         }
     };
+    println!("{}", output);
     Ok(())
 }
@@ -1,13 +1,24 @@
-use cairo_platinum_prover::{air::{generate_cairo_proof, verify_cairo_proof, PublicInputs}, cairo_mem::CairoMemory, execution_trace::build_main_trace, register_states::RegisterStates};
+use std::collections::HashMap;
+
+use cairo_platinum_prover::{air::{generate_cairo_proof, verify_cairo_proof, MemorySegment, PublicInputs, Segment}, cairo_mem::CairoMemory, execution_trace::build_main_trace, register_states::RegisterStates, Felt252};
 use hyle_contract::HyleOutput;
-use stark_platinum_prover::proof::options::{ProofOptions, SecurityLevel};
+use stark_platinum_prover::proof::options::ProofOptions;
 use serde::{Deserialize, Serialize};
 use lambdaworks_math::field::fields::fft_friendly::stark_252_prime_field::Stark252PrimeField;
+use lambdaworks_math::traits::ByteConversion;
+use lambdaworks_crypto::hash::pedersen::{Pedersen, PedersenStarkCurve};
 use stark_platinum_prover::proof::stark::StarkProof;
 use error::VerifierError;
-use num::BigInt;
+use hyle_verifier_core::VerifyError;
+use num::{BigInt, BigUint, ToPrimitive};
+use container::ProofContainer;
+use options::{CairoProofOptions, Security};
 
+pub mod container;
 pub mod error;
+pub mod options;
+pub mod program;
+pub mod stack;
 
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Event {
@@ -17,73 +28,200 @@ pub struct Event {
     score: Option<u64>
 }
 
-pub fn verify_proof(proof_path: &String) -> Result<String, VerifierError>{
-    let proof_options = ProofOptions::new_secure(SecurityLevel::Conjecturable100Bits, 3);
+/// Subset of the `--air_public_input` file written by the Cairo runner.
+/// Only the memory segments are needed to locate the program output.
+#[derive(Deserialize, Debug)]
+pub struct AirPublicInput {
+    pub memory_segments: HashMap<String, AirSegment>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct AirSegment {
+    pub begin_addr: u64,
+    pub stop_ptr: u64,
+}
+
+pub fn verify_proof(program_hash: &String, proof_path: &String, min_security: Security) -> Result<String, VerifyError>{
     let Ok(program_content) = std::fs::read(proof_path) else {
-        return Err(VerifierError(format!("Error opening {} file", proof_path)));
+        return Err(VerifyError::Io(format!("Error opening {} file", proof_path)));
     };
-    let mut bytes = program_content.as_slice();
-    if bytes.len() < 8 {
-        return Err(VerifierError(format!("Error reading proof from file: {}", proof_path)));
+    let program_output = verify_proof_bytes(program_hash, &program_content, min_security)?;
+    serde_json::to_string(&program_output).map_err(|err| VerifyError::Internal(err.to_string()))
+}
+
+/// Verifies a proof written by `prove`, with the options it records, which must be at least
+/// as secure as `min_security`.
+pub fn verify_proof_bytes(program_hash: &str, program_content: &[u8], min_security: Security) -> Result<HyleOutput<Event>, VerifyError>{
+    let container = ProofContainer::read(program_content)?;
+    let proof = decode_section(container.proof, "proof")?;
+    let pub_inputs = decode_section(container.public_inputs, "public inputs")?;
+    let claimed_output: HyleOutput<Event> = decode_section(container.output, "output")?;
+
+    let options = container.options;
+    if !options.security.meets(min_security) {
+        return Err(VerifyError::VerificationFailed(format!(
+            "Proof security {} is below the minimum {}",
+            options.security, min_security
+        )));
     }
 
-    // Proof len was stored as an u32, 4u8 needs to be read
-    let proof_len = u32::from_le_bytes(bytes[0..4].try_into().unwrap()) as usize;
+    if !verify_cairo_proof(&proof, &pub_inputs, &options.proof_options()) {
+        return Err(VerifyError::VerificationFailed("Proof verification failed".to_string()));
+    }
 
-    bytes = &bytes[4..];
-    if bytes.len() < proof_len {
-        return Err(VerifierError(format!("Error reading proof from file: {}", proof_path)));
+    // Any valid execution would pass the check above: bind it to the expected program
+    let proven_program_hash = program_hash_from_public_inputs(&pub_inputs)?;
+    if parse_felt_hex(program_hash)? != proven_program_hash {
+        return Err(VerifyError::WrongProgramId(format!(
+            "Program hash mismatch: expected {}, proof is for 0x{:x}",
+            program_hash, proven_program_hash
+        )));
     }
-    
-    let Ok((proof, _)) = bincode::serde::decode_from_slice(
-        &bytes[0..proof_len],
-        bincode::config::standard(),
-    ) else {
-        return Err(VerifierError(format!("Error reading proof from file: {}", proof_path)));
-    };
-    
-    // PublicInputs len was stored as an u32, 4u8 needs to be read
-    let pub_inputs_len = u32::from_le_bytes(bytes[proof_len..proof_len+4].try_into().unwrap()) as usize;
-    let pub_inputs_bytes = &bytes[proof_len+4..proof_len+4+pub_inputs_len];
-    
-    let Ok((pub_inputs, _)) =
-    bincode::serde::decode_from_slice(pub_inputs_bytes, bincode::config::standard())
-    else {
-        return Err(VerifierError(format!("Error reading proof from file: {}", proof_path)));
+
+    // and to a run of the whole program, from __start__ to __end__
+    program::check_entry_and_exit(
+        register(&pub_inputs.pc_init)?,
+        register(&pub_inputs.pc_final)?,
+        pub_inputs.codelen as u64,
+        |addr| pub_inputs.public_memory.get(&Felt252::from(addr)).and_then(felt_to_u64),
+    )?;
+
+    // The appended output is not covered by the proof: only the output segment
+    // of the public memory is. Rebuild the output from it and make sure the
+    // claimed one is the same.
+    let program_output = output_from_public_inputs(&pub_inputs)?;
+    if !same_output(&program_output, &claimed_output)? {
+        return Err(VerifyError::VerificationFailed("Claimed program output does not match the proven output".to_string()));
+    }
+    Ok(program_output)
+}
+
+/// Decodes a section of the proof file, which must hold nothing else.
+fn decode_section<T: serde::de::DeserializeOwned>(bytes: &[u8], name: &str) -> Result<T, VerifyError> {
+    let Ok((value, len)) = bincode::serde::decode_from_slice(bytes, bincode::config::standard()) else {
+        return Err(VerifyError::MalformedProof(format!("Error reading proof {}", name)));
     };
-    let program_output_bytes = &bytes[proof_len+4+pub_inputs_len..];
+    if len != bytes.len() {
+        return Err(VerifyError::MalformedProof(format!("Unexpected bytes after the proof {}", name)));
+    }
+    Ok(value)
+}
 
-    let Ok((program_output, _)) =
-        bincode::serde::decode_from_slice::<HyleOutput<Event>, _>(program_output_bytes, bincode::config::standard())
-    else {
-        return Err(VerifierError(format!("Error reading proof from file: {}", proof_path)));
+/// Reads the output segment from the public memory and parses it as an HyleOutput.
+/// The segment must be the one the initial and final stacks point to, see [stack].
+pub fn output_from_public_inputs(pub_inputs: &PublicInputs) -> Result<HyleOutput<Event>, VerifyError> {
+    let Some(output_segment) = pub_inputs.memory_segments.get(&MemorySegment::Output) else {
+        return Err(VerifyError::MalformedProof("Proof has no output segment in its public inputs".to_string()));
     };
+    stack::check_output_segment(
+        output_segment.begin_addr as u64,
+        output_segment.stop_ptr as u64,
+        register(&pub_inputs.ap_init)?,
+        register(&pub_inputs.ap_final)?,
+        |addr| pub_inputs.public_memory.get(&Felt252::from(addr)).and_then(felt_to_u64),
+    )?;
+
+    let mut felts = vec![];
+    for addr in output_segment.begin_addr..output_segment.stop_ptr {
+        let Some(value) = pub_inputs.public_memory.get(&Felt252::from(addr as u64)) else {
+            return Err(VerifyError::MalformedProof(format!("Output cell {} is missing from the public memory", addr)));
+        };
+        felts.push(BigUint::from_bytes_be(&value.to_bytes_be()).to_string());
+    }
+    // Same format as the output printed by the cairo runner
+    <HyleOutput<Event> as DeserializableHyleOutput>::deserialize(&format!("[{}]", felts.join(" ")))
+        .map_err(|err| VerifyError::OutputDecode(err.0))
+}
 
-    if verify_cairo_proof(&proof, &pub_inputs, &proof_options){
-        return Ok(serde_json::to_string(&program_output)?);
+/// Computes the hash of the program bytecode stored in the public memory.
+/// This is the pedersen hash chain used by `cairo-hash-program`:
+/// H(len, H(p_0, H(p_1, ... H(p_n-2, p_n-1))))
+pub fn program_hash_from_public_inputs(pub_inputs: &PublicInputs) -> Result<BigUint, VerifyError> {
+    if pub_inputs.codelen == 0 {
+        return Err(VerifyError::MalformedProof("Proof has no program in its public memory".to_string()));
     }
-    else {
-        return Err(VerifierError(format!("Error reading proof from file: {}", proof_path)));
+
+    let mut data = vec![Felt252::from(pub_inputs.codelen as u64)];
+    for addr in program::PROGRAM_BASE..program::PROGRAM_BASE.saturating_add(pub_inputs.codelen as u64) {
+        let Some(value) = pub_inputs.public_memory.get(&Felt252::from(addr)) else {
+            return Err(VerifyError::MalformedProof(format!("Program cell {} is missing from the public memory", addr)));
+        };
+        data.push(value.clone());
+    }
+
+    let mut felts = data.iter().rev();
+    let mut hash = felts.next().unwrap().clone();
+    for felt in felts {
+        hash = PedersenStarkCurve::hash(felt, &hash);
     }
+    Ok(BigUint::from_bytes_be(&hash.to_bytes_be()))
+}
+
+fn felt_to_u64(felt: &Felt252) -> Option<u64> {
+    BigUint::from_bytes_be(&felt.to_bytes_be()).to_u64()
 }
 
+fn register(felt: &Felt252) -> Result<u64, VerifyError> {
+    felt_to_u64(felt).ok_or_else(|| VerifyError::MalformedProof("Register out of range".to_string()))
+}
 
-pub fn prove(trace_data: Vec<u8>, memory_data: Vec<u8>, output: &str) -> Result<Vec<u8>, VerifierError> {
-    let proof_options = ProofOptions::new_secure(SecurityLevel::Conjecturable100Bits, 3);
+fn parse_felt_hex(s: &str) -> Result<BigUint, VerifyError> {
+    let digits = s.strip_prefix("0x").unwrap_or(s);
+    // parse_bytes accepts a sign and underscores, which are not hex
+    if digits.is_empty() || !digits.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return Err(VerifyError::WrongProgramId(format!("Invalid program hash: {}", s)));
+    }
+    BigUint::parse_bytes(digits.as_bytes(), 16)
+        .ok_or_else(|| VerifyError::WrongProgramId(format!("Invalid program hash: {}", s)))
+}
+
+fn same_output(a: &HyleOutput<Event>, b: &HyleOutput<Event>) -> Result<bool, VerifyError> {
+    let encode = |output| {
+        bincode::serde::encode_to_vec(output, bincode::config::standard())
+            .map_err(|err| VerifyError::Internal(err.to_string()))
+    };
+    let (a, b) = (encode(a)?, encode(b)?);
+    Ok(a == b)
+}
+
+
+pub fn prove(trace_data: Vec<u8>, memory_data: Vec<u8>, air_public_input: &str, output: &str, options: CairoProofOptions) -> Result<Vec<u8>, VerifierError> {
+    options.validate().map_err(VerifierError)?;
+    let proof_options = options.proof_options();
+    let air_public_input: AirPublicInput = serde_json::from_str(air_public_input)?;
+    let Some(program_segment) = air_public_input.memory_segments.get("program") else {
+        return Err(VerifierError("AIR public input has no program segment".to_string()));
+    };
+    if program_segment.begin_addr != 1 {
+        return Err(VerifierError("Program segment is expected to start at address 1".to_string()));
+    }
+    let Some(output_segment) = air_public_input.memory_segments.get("output") else {
+        return Err(VerifierError("AIR public input has no output segment".to_string()));
+    };
     let Some((proof, pub_inputs)) = generate_proof_from_trace(
         &trace_data,
         &memory_data,
+        *program_segment,
+        *output_segment,
         &proof_options,
     ) else {
         return Err(VerifierError("Error generation prover args".to_string()));
     };
-    let proof = write_proof(proof, pub_inputs, output);
+
+    // Fail early instead of producing a proof the verifier would reject
+    let program_output = <HyleOutput<Event> as DeserializableHyleOutput>::deserialize(output)?;
+    if !same_output(&output_from_public_inputs(&pub_inputs)?, &program_output)? {
+        return Err(VerifierError("Program output does not match the output segment of the execution".to_string()));
+    }
+    let proof = write_proof(proof, pub_inputs, program_output, options);
     Ok(proof)
 }
 
 pub fn generate_proof_from_trace(
     trace_data: &Vec<u8>,
     memory_data: &Vec<u8>,
+    program_segment: AirSegment,
+    output_segment: AirSegment,
     proof_options: &ProofOptions,
 ) -> Option<(
     StarkProof<Stark252PrimeField, Stark252PrimeField>,
@@ -93,10 +231,45 @@ pub fn generate_proof_from_trace(
     let register_states = RegisterStates::from_bytes_le(trace_data).expect("Cairo trace data incorrect");
     let memory = CairoMemory::from_bytes_le(memory_data).expect("Cairo memory data incorrect");
 
-    // data length
-    let data_len = 0_usize;
-    let mut pub_inputs = PublicInputs::from_regs_and_mem(&register_states, &memory, data_len);
+    // The program bytecode is made public so that the verifier can check which program was run
+    let codelen = (program_segment.stop_ptr - program_segment.begin_addr) as usize;
+    let mut pub_inputs = PublicInputs::from_regs_and_mem(&register_states, &memory, codelen);
 
+    // The output segment is made public so that the proof commits to the program output
+    for addr in output_segment.begin_addr..output_segment.stop_ptr {
+        let Some(value) = memory.get(&addr) else {
+            eprintln!("Output cell {} is missing from the memory", addr);
+            return None;
+        };
+        pub_inputs.public_memory.insert(Felt252::from(addr), *value);
+    }
+    pub_inputs.memory_segments.insert(
+        MemorySegment::Output,
+        Segment { begin_addr: output_segment.begin_addr as usize, stop_ptr: output_segment.stop_ptr as usize },
+    );
+
+    // So are the initial and final stacks, from which the verifier checks the output segment
+    let (Some(ap_init), Some(ap_final)) = (felt_to_u64(&pub_inputs.ap_init), felt_to_u64(&pub_inputs.ap_final)) else {
+        eprintln!("Registers are out of range");
+        return None;
+    };
+    let memory_cell = |addr: u64| memory.get(&addr).and_then(felt_to_u64);
+    let stack_cells = stack::builtins_count(ap_init, memory_cell)
+        .and_then(|builtins| stack::stack_cells(ap_init, ap_final, builtins));
+    let stack_cells = match stack_cells {
+        Ok(cells) => cells,
+        Err(err) => {
+            eprintln!("Error reading the stack: {}", err);
+            return None;
+        }
+    };
+    for addr in stack_cells {
+        let Some(value) = memory.get(&addr) else {
+            eprintln!("Stack cell {} is missing from the memory", addr);
+            return None;
+        };
+        pub_inputs.public_memory.insert(Felt252::from(addr), *value);
+    }
 
     let main_trace = build_main_trace(&register_states, &memory, &mut pub_inputs);
 
@@ -116,70 +289,75 @@ pub fn generate_proof_from_trace(
 fn write_proof(
     proof: StarkProof<Stark252PrimeField, Stark252PrimeField>,
     pub_inputs: PublicInputs,
-    output: &str,
+    program_output: HyleOutput<Event>,
+    options: CairoProofOptions,
 ) -> Vec<u8> {
-    let mut bytes = vec![];
     let proof_bytes: Vec<u8> =
         bincode::serde::encode_to_vec(proof, bincode::config::standard()).unwrap();
 
-    let pub_inputs_bytes: Vec<u8> =
-        bincode::serde::encode_to_vec(&pub_inputs, bincode::config::standard()).unwrap();    
-
     // This should be reworked
     // Public inputs shouldn't be stored in the proof if the verifier wants to check them
-
-    // An u32 is enough for storing proofs up to 32 GiB
-    // They shouldn't exceed the order of kbs
-    // Reading an usize leads to problem in WASM (32 bit vs 64 bit architecture)
-
-    bytes.extend((proof_bytes.len() as u32).to_le_bytes());
-    bytes.extend(proof_bytes);
-    bytes.extend((pub_inputs_bytes.len() as u32).to_le_bytes());
-    bytes.extend(pub_inputs_bytes);
+    let pub_inputs_bytes: Vec<u8> =
+        bincode::serde::encode_to_vec(&pub_inputs, bincode::config::standard()).unwrap();
 
     ///// HYLE CUSTOM /////
     // Basically adding the program output to the proof
-    let program_output = <HyleOutput<Event> as DeserializableHyleOutput>::deserialize(&output);
+    // The verifier checks it against the output segment of the public memory
     let program_output_bytes: Vec<u8> =
         bincode::serde::encode_to_vec(&program_output, bincode::config::standard()).unwrap();
-    bytes.extend(program_output_bytes);
     ///////////////////////
-    bytes
+
+    ProofContainer {
+        version: container::VERSION,
+        proof: &proof_bytes,
+        public_inputs: &pub_inputs_bytes,
+        output: &program_output_bytes,
+        options,
+    }
+    .to_bytes()
 }
 
 
 
-pub trait DeserializableHyleOutput {
-    fn i_to_w(s: String) -> String;
-    fn deserialize_cairo_bytesarray(data: &mut Vec<&str>) -> String;
-    fn deserialize(input: &str) -> Self;
+pub trait DeserializableHyleOutput: Sized {
+    fn i_to_w(s: String) -> Result<String, VerifierError>;
+    fn deserialize_cairo_bytesarray(data: &mut Vec<&str>) -> Result<String, VerifierError>;
+    fn deserialize(input: &str) -> Result<Self, VerifierError>;
 }
 
+/// Pops the next felt of the output, failing instead of panicking on truncated outputs.
+fn next_felt<'a>(data: &mut Vec<&'a str>) -> Result<&'a str, VerifierError> {
+    if data.is_empty() {
+        return Err(VerifierError("Program output is too short".to_string()));
+    }
+    Ok(data.remove(0))
+}
 
 impl DeserializableHyleOutput for HyleOutput<Event> {
     /// Receives an int, change base to hex, decode it to ascii
-    fn i_to_w(s: String) -> String {
-        let int = s.parse::<BigInt>().expect("failed to parse the address");
-        let hex = hex::decode(format!("{:x}", int)).expect("failed to parse the address");
-        String::from_utf8(hex).expect("failed to parse the address")
+    fn i_to_w(s: String) -> Result<String, VerifierError> {
+        let int = s.parse::<BigInt>()?;
+        let hex = hex::decode(format!("{:x}", int))?;
+        Ok(String::from_utf8(hex)?)
     }
 
     /// BytesArray serialisation is composed of 3 values (if the data is less than 31bytes)
     /// https://github.com/starkware-libs/cairo/blob/main/corelib/src/byte_array.cairo#L24-L34
-    /// WARNING: Deserialization is not yet robust.
     /// TODO: pending_word_len not used.
-    /// TODO: add checking on inputs.
-    fn deserialize_cairo_bytesarray(data: &mut Vec<&str>) -> String {
-        let pending_word = data.remove(0).parse::<usize>().unwrap();
-        let _pending_word_len = data.remove(pending_word + 1).parse::<usize>().unwrap();
+    fn deserialize_cairo_bytesarray(data: &mut Vec<&str>) -> Result<String, VerifierError> {
+        let pending_word = next_felt(data)?.parse::<usize>()?;
+        if pending_word.checked_add(2).map_or(true, |needed| data.len() < needed) {
+            return Err(VerifierError("Program output is too short".to_string()));
+        }
+        let _pending_word_len = data.remove(pending_word + 1).parse::<usize>()?;
         let mut word: String = "".into();
         for _ in 0..pending_word+1 {
-            let d: String = data.remove(0).into();
+            let d: String = next_felt(data)?.into();
             if d != "0"{
-                word.push_str(&Self::i_to_w(d));
+                word.push_str(&Self::i_to_w(d)?);
             }
         }
-        word
+        Ok(word)
     }
 
     /// Deserialize the output of the cairo erc20 contract.
@@ -187,70 +365,195 @@ impl DeserializableHyleOutput for HyleOutput<Event> {
     /// elements for the "to" address
     /// [-2] element for the amount transfered
     /// [-1] element for the next state
-    fn deserialize(input: &str) -> Self {
-        let trimmed = input.trim_matches(|c| c == '[' || c == ']');
+    fn deserialize(input: &str) -> Result<Self, VerifierError> {
+        let trimmed = input.trim().trim_matches(|c| c == '[' || c == ']');
         let mut parts: Vec<&str> = trimmed.split_whitespace().collect();
         // extract version
-        let version = parts.remove(0).parse::<u32>().unwrap();
+        let version = next_felt(&mut parts)?.parse::<u32>()?;
         // extract initial_state
-        let initial_state: String = parts.remove(0).parse::<String>().unwrap();
+        let initial_state: String = next_felt(&mut parts)?.to_string();
         // extract next_state
-        let next_state: String = parts.remove(0).parse::<String>().unwrap();
+        let next_state: String = next_felt(&mut parts)?.to_string();
         // extract origin
-        let origin: String = Self::deserialize_cairo_bytesarray(&mut parts);
+        let origin: String = Self::deserialize_cairo_bytesarray(&mut parts)?;
         // extract caller
-        let caller: String = Self::deserialize_cairo_bytesarray(&mut parts);
+        let caller: String = Self::deserialize_cairo_bytesarray(&mut parts)?;
         // extract tx_hash
-        let tx_hash: String = parts.remove(0).parse::<String>().unwrap();
+        let tx_hash: String = next_felt(&mut parts)?.to_string();
 
-        let output = match parts.len() {
+        let program_outputs = match parts.len() {
             1 => {
-                let score = parts.remove(0).parse::<u64>().unwrap();
-                let program_outputs = Event {
+                let score = next_felt(&mut parts)?.parse::<u64>()?;
+                Event {
                     score: Some(score),
                     ..Default::default()
-                };
-                HyleOutput {
-                    version,
-                    initial_state: initial_state.as_bytes().to_vec(),
-                    next_state: next_state.as_bytes().to_vec(),
-                    origin,
-                    caller,
-                    block_number: 0,
-                    block_time: 0,
-                    tx_hash: tx_hash.as_bytes().to_vec(),
-                    program_outputs
                 }
             },
             7 => {
                 // extract from
-                let from = Self::deserialize_cairo_bytesarray(&mut parts);
+                let from = Self::deserialize_cairo_bytesarray(&mut parts)?;
                 // extract to
-                let to = Self::deserialize_cairo_bytesarray(&mut parts);
+                let to = Self::deserialize_cairo_bytesarray(&mut parts)?;
                 // extract amount
-                let amount = parts.remove(0).parse::<u64>().unwrap();
+                let amount = next_felt(&mut parts)?.parse::<u64>()?;
 
-                let program_outputs = Event {
+                Event {
                     from: Some(from),
                     to: Some(to),
                     amount: Some(amount),
                      ..Default::default()
-                };
-                HyleOutput {
-                    version,
-                    initial_state: initial_state.as_bytes().to_vec(),
-                    next_state: next_state.as_bytes().to_vec(),
-                    origin,
-                    caller,
-                    block_number: 0,
-                    block_time: 0,
-                    tx_hash: tx_hash.as_bytes().to_vec(),
-                    program_outputs
                 }
             }
-            _ => panic!("You're not parsing ERC20 or ML. Sorry bro not possible atm. Or your name is too long :eyes:"),
+            _ => return Err(VerifierError("You're not parsing ERC20 or ML. Sorry bro not possible atm. Or your name is too long :eyes:".to_string())),
         };
 
-        output
+        Ok(HyleOutput {
+            version,
+            initial_state: initial_state.as_bytes().to_vec(),
+            next_state: next_state.as_bytes().to_vec(),
+            origin,
+            caller,
+            block_number: 0,
+            block_time: 0,
+            tx_hash: tx_hash.as_bytes().to_vec(),
+            program_outputs
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use cairo_platinum_prover::air::{MemorySegment, PublicInputs, Segment};
+    use cairo_platinum_prover::Felt252;
+    use hyle_verifier_core::VerifyError;
+    use lambdaworks_crypto::hash::pedersen::{Pedersen, PedersenStarkCurve};
+    use lambdaworks_math::traits::ByteConversion;
+    use num::BigUint;
+
+    use super::{output_from_public_inputs, parse_felt_hex, program_hash_from_public_inputs, same_output};
+
+    // version, initial and next states, origin "alice" and caller "bob" as byte arrays,
+    // tx hash and score
+    const OUTPUT: [u64; 11] = [1, 10, 11, 0, 0x616c696365, 5, 0, 0x626f62, 3, 12, 42];
+    const AP_INIT: u64 = 20;
+    const AP_FINAL: u64 = 40;
+    const OUTPUT_BASE: u64 = 100;
+
+    fn pub_inputs(codelen: usize, public_memory: HashMap<Felt252, Felt252>) -> PublicInputs {
+        PublicInputs {
+            pc_init: Felt252::from(1u64),
+            ap_init: Felt252::zero(),
+            fp_init: Felt252::zero(),
+            pc_final: Felt252::zero(),
+            ap_final: Felt252::zero(),
+            range_check_min: None,
+            range_check_max: None,
+            memory_segments: HashMap::new(),
+            public_memory,
+            num_steps: 0,
+            codelen,
+        }
+    }
+
+    /// Public inputs of an execution with the output builtin only, which output these felts.
+    fn output_pub_inputs(output: &[u64]) -> PublicInputs {
+        let stop = OUTPUT_BASE + output.len() as u64;
+        let mut memory = HashMap::from([
+            (Felt252::from(AP_INIT), Felt252::from(OUTPUT_BASE)),
+            (Felt252::from(AP_INIT + 1), Felt252::from(AP_INIT)),
+            (Felt252::from(AP_FINAL - 1), Felt252::from(stop)),
+        ]);
+        for (addr, value) in (OUTPUT_BASE..).zip(output) {
+            memory.insert(Felt252::from(addr), Felt252::from(*value));
+        }
+        let mut pub_inputs = pub_inputs(0, memory);
+        pub_inputs.ap_init = Felt252::from(AP_INIT);
+        pub_inputs.ap_final = Felt252::from(AP_FINAL);
+        pub_inputs.memory_segments.insert(
+            MemorySegment::Output,
+            Segment { begin_addr: OUTPUT_BASE as usize, stop_ptr: stop as usize },
+        );
+        pub_inputs
+    }
+
+    #[test]
+    fn test_output_from_public_inputs() {
+        let output = output_from_public_inputs(&output_pub_inputs(&OUTPUT)).unwrap();
+        assert_eq!(output.version, 1);
+        assert_eq!(output.initial_state, b"10");
+        assert_eq!(output.next_state, b"11");
+        assert_eq!(output.origin, "alice");
+        assert_eq!(output.caller, "bob");
+        assert_eq!(output.tx_hash, b"12");
+        assert_eq!(output.program_outputs.score, Some(42));
+
+        // The output claimed with the proof must be the proven one
+        let claimed = output_from_public_inputs(&output_pub_inputs(&OUTPUT)).unwrap();
+        assert!(same_output(&output, &claimed).unwrap());
+        let mut other = OUTPUT;
+        other[10] = 43;
+        let claimed = output_from_public_inputs(&output_pub_inputs(&other)).unwrap();
+        assert!(!same_output(&output, &claimed).unwrap());
+
+        // The segment given with the public inputs must be the one of the execution
+        let mut pub_inputs = output_pub_inputs(&OUTPUT);
+        pub_inputs.memory_segments.insert(
+            MemorySegment::Output,
+            Segment { begin_addr: OUTPUT_BASE as usize, stop_ptr: OUTPUT_BASE as usize + 10 },
+        );
+        let err = output_from_public_inputs(&pub_inputs).unwrap_err();
+        assert!(matches!(err, VerifyError::VerificationFailed(_)), "{:?}", err);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_malformed_output() {
+        let mut oversized = OUTPUT.to_vec();
+        oversized.extend([1, 2]);
+        // A byte array with more pending words than the output has felts
+        let mut huge_array = OUTPUT;
+        huge_array[3] = u64::MAX;
+        for output in [&OUTPUT[..0], &OUTPUT[..5], &OUTPUT[..10], &oversized, &huge_array] {
+            let err = output_from_public_inputs(&output_pub_inputs(output)).unwrap_err();
+            assert!(matches!(err, VerifyError::OutputDecode(_)), "{:?}", err);
+        }
+    }
+
+    #[test]
+    fn test_parse_felt_hex() {
+        assert_eq!(parse_felt_hex("0x1f").unwrap(), BigUint::from(31u32));
+        assert_eq!(parse_felt_hex("1F").unwrap(), BigUint::from(31u32));
+        assert_eq!(parse_felt_hex("0x0001").unwrap(), BigUint::from(1u32));
+        for invalid in ["", "0x", "0xg1", "+1f", "0x1_f", "0X1f", " 1f"] {
+            let err = parse_felt_hex(invalid).unwrap_err();
+            assert!(matches!(err, VerifyError::WrongProgramId(_)), "{:?} accepted", invalid);
+        }
+    }
+
+    #[test]
+    fn test_program_hash() {
+        // Pedersen test vector of starknet
+        let a = Felt252::from_hex_unchecked("3d937c035c878245caf64531a5756109c53068da139362728feb561405371cb");
+        let b = Felt252::from_hex_unchecked("208a0a10250e382e1e4bbe2880906c2791bf6275695e02fbbc6aeff9cd8b31a");
+        let hash_ab = Felt252::from_hex_unchecked("30e480bed5fe53fa909cc0f8c4d99b8f9f2c016be4c41e13a4848797979c662");
+        assert_eq!(PedersenStarkCurve::hash(&a, &b), hash_ab);
+
+        // H(2, H(p_0, p_1)), the program being at 1 and 2
+        let memory = HashMap::from([(Felt252::from(1u64), a), (Felt252::from(2u64), b), (Felt252::from(3u64), a)]);
+        let expected = PedersenStarkCurve::hash(&Felt252::from(2u64), &hash_ab);
+        assert_eq!(
+            program_hash_from_public_inputs(&pub_inputs(2, memory.clone())).unwrap(),
+            BigUint::from_bytes_be(&expected.to_bytes_be())
+        );
+        // Another length hashes other cells
+        assert_ne!(
+            program_hash_from_public_inputs(&pub_inputs(3, memory.clone())).unwrap(),
+            BigUint::from_bytes_be(&expected.to_bytes_be())
+        );
+
+        assert!(program_hash_from_public_inputs(&pub_inputs(0, memory.clone())).is_err());
+        // The program is longer than the public memory
+        assert!(program_hash_from_public_inputs(&pub_inputs(4, memory)).is_err());
+    }
+}
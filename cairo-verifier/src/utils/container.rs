@@ -0,0 +1,261 @@
+//! File format of the proofs written by `prove`.
+//!
+//! ```text
+//! magic     8 bytes   b"HYLECAIR"
+//! version   u16 LE
+//! count     u16 LE    number of sections
+//! table     count entries of 42 bytes: kind u16 LE, offset u32 LE, length u32 LE, SHA-256
+//! sections  in table order, right after the table
+//! ```
+//! Each section is bincode encoded: the STARK proof, its public inputs, the claimed
+//! HyleOutput and the proof options. Proofs written before this format, a length prefixed
+//! proof and public inputs followed by the output, are rejected: their public inputs lack
+//! the output segment, so their output can not be verified.
+
+use hyle_verifier_core::VerifyError;
+use sha2::{Digest, Sha256};
+
+use super::options::CairoProofOptions;
+
+pub const MAGIC: &[u8; 8] = b"HYLECAIR";
+/// Version written by `prove`. Proofs of version 1 lack the public stack cells the output
+/// segment is checked against, see [super::stack].
+pub const VERSION: u16 = 2;
+
+const HEADER_LEN: usize = 12;
+const ENTRY_LEN: usize = 42;
+
+/// Kinds of sections, as stored in the section table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u16)]
+pub enum SectionKind {
+    Proof = 1,
+    PublicInputs = 2,
+    Output = 3,
+    Options = 4,
+}
+
+impl SectionKind {
+    const ALL: [SectionKind; 4] = [
+        SectionKind::Proof,
+        SectionKind::PublicInputs,
+        SectionKind::Output,
+        SectionKind::Options,
+    ];
+}
+
+/// Sections of a proof file, still bincode encoded but for the options.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ProofContainer<'a> {
+    pub version: u16,
+    pub proof: &'a [u8],
+    pub public_inputs: &'a [u8],
+    pub output: &'a [u8],
+    pub options: CairoProofOptions,
+}
+
+impl<'a> ProofContainer<'a> {
+    /// Reads a proof file, failing on anything malformed instead of panicking.
+    pub fn read(bytes: &'a [u8]) -> Result<Self, VerifyError> {
+        if !bytes.starts_with(MAGIC) {
+            return Err(malformed(
+                "Not a Cairo proof file, or one written by an older prover without its output \
+                 segment, which can not be verified: prove it again"
+                    .to_string(),
+            ));
+        }
+        let mut reader = Reader { bytes, at: MAGIC.len() };
+        let version = reader.u16()?;
+        if version != VERSION {
+            return Err(malformed(format!(
+                "Unsupported proof version {}, expected {}",
+                version, VERSION
+            )));
+        }
+        let count = reader.u16()? as usize;
+
+        let mut sections: [Option<&[u8]>; 4] = [None; 4];
+        // Sections follow the table, in its order and without gaps
+        let mut next = HEADER_LEN + count * ENTRY_LEN;
+        for _ in 0..count {
+            let kind = reader.u16()?;
+            let offset = reader.u32()? as usize;
+            let len = reader.u32()? as usize;
+            let checksum = reader.take(32)?;
+            if offset != next {
+                return Err(malformed(format!("Section {} is not where expected", kind)));
+            }
+            let data = offset
+                .checked_add(len)
+                .and_then(|end| bytes.get(offset..end))
+                .ok_or_else(|| malformed(format!("Section {} is out of bounds", kind)))?;
+            if Sha256::digest(data).as_slice() != checksum {
+                return Err(malformed(format!("Section {} has a wrong checksum", kind)));
+            }
+            next = offset + len;
+
+            let Some(index) = SectionKind::ALL.iter().position(|known| *known as u16 == kind) else {
+                return Err(malformed(format!("Unknown section {}", kind)));
+            };
+            if sections[index].replace(data).is_some() {
+                return Err(malformed(format!("Duplicate section {}", kind)));
+            }
+        }
+        if next != bytes.len() {
+            return Err(malformed("Unexpected bytes after the last section".to_string()));
+        }
+
+        let section = |kind: SectionKind| {
+            sections[kind as usize - 1]
+                .ok_or_else(|| malformed(format!("Missing {:?} section", kind)))
+        };
+        Ok(ProofContainer {
+            version,
+            proof: section(SectionKind::Proof)?,
+            public_inputs: section(SectionKind::PublicInputs)?,
+            output: section(SectionKind::Output)?,
+            options: decode_options(section(SectionKind::Options)?)?,
+        })
+    }
+
+    /// Writes the sections in the current format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let options = bincode::serde::encode_to_vec(self.options, bincode::config::standard())
+            .expect("Options always encode");
+        let sections: [(SectionKind, &[u8]); 4] = [
+            (SectionKind::Proof, self.proof),
+            (SectionKind::PublicInputs, self.public_inputs),
+            (SectionKind::Output, self.output),
+            (SectionKind::Options, &options),
+        ];
+
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(VERSION.to_le_bytes());
+        bytes.extend((sections.len() as u16).to_le_bytes());
+        let mut offset = HEADER_LEN + sections.len() * ENTRY_LEN;
+        for (kind, data) in sections {
+            bytes.extend((kind as u16).to_le_bytes());
+            // An u32 is enough for sections up to 4 GiB, proofs are far smaller
+            bytes.extend((offset as u32).to_le_bytes());
+            bytes.extend((data.len() as u32).to_le_bytes());
+            bytes.extend(Sha256::digest(data));
+            offset += data.len();
+        }
+        for (_, data) in sections {
+            bytes.extend(data);
+        }
+        bytes
+    }
+}
+
+fn decode_options(bytes: &[u8]) -> Result<CairoProofOptions, VerifyError> {
+    let Ok((options, len)) =
+        bincode::serde::decode_from_slice::<CairoProofOptions, _>(bytes, bincode::config::standard())
+    else {
+        return Err(malformed("Error reading proof options".to_string()));
+    };
+    if len != bytes.len() {
+        return Err(malformed("Unexpected bytes after the proof options".to_string()));
+    }
+    options.validate().map_err(malformed)?;
+    Ok(options)
+}
+
+fn malformed(message: String) -> VerifyError {
+    VerifyError::MalformedProof(message)
+}
+
+/// Bounds checked reads of little endian integers and slices.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], VerifyError> {
+        let data = self
+            .at
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(self.at..end))
+            .ok_or_else(|| malformed("Proof file is truncated".to_string()))?;
+        self.at += len;
+        Ok(data)
+    }
+
+    fn u16(&mut self) -> Result<u16, VerifyError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, VerifyError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hyle_contract::HyleOutput;
+    use hyle_verifier_core::VerifyError;
+
+    use super::{ProofContainer, VERSION};
+    use crate::utils::options::{CairoProofOptions, Security};
+    use crate::utils::Event;
+
+    fn output() -> Vec<u8> {
+        let output = HyleOutput {
+            version: 1,
+            initial_state: vec![1],
+            next_state: vec![2],
+            origin: "alice".to_string(),
+            caller: "bob".to_string(),
+            block_number: 0,
+            block_time: 0,
+            tx_hash: vec![3],
+            program_outputs: Event::default(),
+        };
+        bincode::serde::encode_to_vec(&output, bincode::config::standard()).unwrap()
+    }
+
+    fn legacy(proof: &[u8], public_inputs: &[u8], output: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![];
+        bytes.extend((proof.len() as u32).to_le_bytes());
+        bytes.extend(proof);
+        bytes.extend((public_inputs.len() as u32).to_le_bytes());
+        bytes.extend(public_inputs);
+        bytes.extend(output);
+        bytes
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let output = output();
+        let container = ProofContainer {
+            version: VERSION,
+            proof: &[1, 2, 3],
+            public_inputs: &[4, 5],
+            output: &output,
+            options: CairoProofOptions { security: Security::Provable100, blowup_factor: Some(4) },
+        };
+        let bytes = container.to_bytes();
+        assert_eq!(ProofContainer::read(&bytes).unwrap(), container);
+
+        // Every truncation, and any flipped byte, is an error rather than a panic
+        for len in 0..bytes.len() {
+            assert!(ProofContainer::read(&bytes[..len]).is_err());
+        }
+        for at in 8..bytes.len() {
+            let mut corrupted = bytes.clone();
+            corrupted[at] ^= 1;
+            assert!(ProofContainer::read(&corrupted).is_err(), "byte {} flipped", at);
+        }
+    }
+
+    #[test]
+    fn test_reject_legacy() {
+        let output = output();
+        let bytes = legacy(&[1, 2, 3], &[4, 5], &output);
+        let Err(VerifyError::MalformedProof(message)) = ProofContainer::read(&bytes) else {
+            panic!("legacy proof was read");
+        };
+        assert!(message.contains("older prover"));
+    }
+}
@@ -0,0 +1,165 @@
+use std::fmt;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use stark_platinum_prover::proof::options::{ProofOptions, SecurityLevel};
+
+/// Security level targeted by a proof, see `SecurityLevel`.
+/// Proofs record its variant index: new levels must be added last.
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Security {
+    Conjecturable80,
+    Conjecturable100,
+    Conjecturable128,
+    Provable80,
+    Provable100,
+    Provable128,
+}
+
+impl Security {
+    pub fn bits(self) -> u32 {
+        match self {
+            Security::Conjecturable80 | Security::Provable80 => 80,
+            Security::Conjecturable100 | Security::Provable100 => 100,
+            Security::Conjecturable128 | Security::Provable128 => 128,
+        }
+    }
+
+    pub fn is_provable(self) -> bool {
+        matches!(self, Security::Provable80 | Security::Provable100 | Security::Provable128)
+    }
+
+    /// Whether this level is at least as secure as the minimum: as many bits, and provable
+    /// when the minimum is.
+    pub fn meets(self, min: Security) -> bool {
+        self.bits() >= min.bits() && (self.is_provable() || !min.is_provable())
+    }
+
+    fn level(self) -> SecurityLevel {
+        match self {
+            Security::Conjecturable80 => SecurityLevel::Conjecturable80Bits,
+            Security::Conjecturable100 => SecurityLevel::Conjecturable100Bits,
+            Security::Conjecturable128 => SecurityLevel::Conjecturable128Bits,
+            Security::Provable80 => SecurityLevel::Provable80Bits,
+            Security::Provable100 => SecurityLevel::Provable100Bits,
+            Security::Provable128 => SecurityLevel::Provable128Bits,
+        }
+    }
+}
+
+/// Same names as on the command line, e.g. `conjecturable100`.
+impl fmt::Display for Security {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = self.to_possible_value().expect("no level is skipped");
+        f.write_str(value.get_name())
+    }
+}
+
+/// Options a proof was generated with, recorded in the proof so that it is verified with them.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CairoProofOptions {
+    pub security: Security,
+    /// Blowup factor of the trace, the one `ProofOptions::new_secure` picks for the security
+    /// level when `None`.
+    pub blowup_factor: Option<u8>,
+}
+
+impl CairoProofOptions {
+    pub const MIN_BLOWUP_FACTOR: u8 = 2;
+    pub const MAX_BLOWUP_FACTOR: u8 = 64;
+    /// Offset of the LDE coset, the one proofs have always been generated with.
+    pub const COSET_OFFSET: u64 = 3;
+
+    /// Checks the options can be used, as the recorded ones are not trusted.
+    pub fn validate(&self) -> Result<(), String> {
+        let Some(blowup_factor) = self.blowup_factor else {
+            return Ok(());
+        };
+        if !(Self::MIN_BLOWUP_FACTOR..=Self::MAX_BLOWUP_FACTOR).contains(&blowup_factor)
+            || !blowup_factor.is_power_of_two()
+        {
+            return Err(format!(
+                "Blowup factor {} is not a power of two between {} and {}",
+                blowup_factor,
+                Self::MIN_BLOWUP_FACTOR,
+                Self::MAX_BLOWUP_FACTOR
+            ));
+        }
+        Ok(())
+    }
+
+    /// Options of the prover and verifier. With a blowup factor, the FRI queries are as many
+    /// as needed to reach the security level with it.
+    pub fn proof_options(&self) -> ProofOptions {
+        let Some(blowup_factor) = self.blowup_factor else {
+            return ProofOptions::new_secure(self.security.level(), Self::COSET_OFFSET);
+        };
+        ProofOptions {
+            blowup_factor,
+            fri_number_of_queries: self.fri_number_of_queries(blowup_factor),
+            coset_offset: Self::COSET_OFFSET,
+            grinding_factor: 0,
+        }
+    }
+
+    /// Each query adds log2(blowup) bits of conjectured security, and log2(2 / (1 + 1/blowup))
+    /// bits of provable security.
+    fn fri_number_of_queries(&self, blowup_factor: u8) -> usize {
+        let blowup_factor = blowup_factor as f64;
+        let bits_per_query = match self.security.is_provable() {
+            true => (2.0 * blowup_factor / (blowup_factor + 1.0)).log2(),
+            false => blowup_factor.log2(),
+        };
+        (self.security.bits() as f64 / bits_per_query).ceil() as usize
+    }
+}
+
+/// Options `prove` uses unless told otherwise.
+impl Default for CairoProofOptions {
+    fn default() -> Self {
+        CairoProofOptions {
+            security: Security::Conjecturable100,
+            blowup_factor: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CairoProofOptions, Security};
+
+    #[test]
+    fn test_minimum_security() {
+        assert!(Security::Conjecturable128.meets(Security::Conjecturable100));
+        assert!(Security::Provable100.meets(Security::Conjecturable100));
+        assert!(!Security::Conjecturable80.meets(Security::Conjecturable100));
+        assert!(!Security::Conjecturable128.meets(Security::Provable100));
+        assert!(!Security::Provable80.meets(Security::Provable100));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(CairoProofOptions::default().validate().is_ok());
+        for blowup_factor in [2, 4, 64] {
+            let options = CairoProofOptions { blowup_factor: Some(blowup_factor), ..Default::default() };
+            assert!(options.validate().is_ok());
+        }
+        for blowup_factor in [0, 1, 3, 6, 128, 255] {
+            let options = CairoProofOptions { blowup_factor: Some(blowup_factor), ..Default::default() };
+            assert!(options.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_fri_number_of_queries() {
+        let queries = |security, blowup_factor| {
+            CairoProofOptions { security, blowup_factor: Some(blowup_factor) }
+                .fri_number_of_queries(blowup_factor)
+        };
+        assert_eq!(queries(Security::Conjecturable100, 4), 50);
+        assert_eq!(queries(Security::Conjecturable128, 16), 32);
+        assert_eq!(queries(Security::Conjecturable80, 8), 27);
+        // log2(2 * 4 / 5) is about 0.678 bits per query
+        assert_eq!(queries(Security::Provable100, 4), 148);
+    }
+}
@@ -0,0 +1,91 @@
+//! Where executions in proof mode start and end in the program.
+//!
+//! Programs compiled with `--proof_mode` start with
+//! ```text
+//! __start__:
+//!     ap += main.Args;
+//!     call main;
+//! __end__:
+//!     jmp rel 0;
+//! ```
+//! and the runner pads the execution with the `__end__` loop. The proof only binds the
+//! execution to the program bytecode: it must also start at `__start__` and end on the
+//! `__end__` loop, else a proof could run any part of the program, from an address chosen by
+//! the prover.
+
+use hyle_verifier_core::VerifyError;
+
+/// Address of the first instruction of the program.
+pub const PROGRAM_BASE: u64 = 1;
+
+/// Encoding of `jmp rel 0`, whose immediate 0 follows.
+pub const JMP_REL_0: u64 = 0x0107_8001_7fff_7fff;
+
+/// Checks the execution starts at the program base and ends on a `jmp rel 0` of the program,
+/// which is `codelen` cells long.
+pub fn check_entry_and_exit(
+    pc_init: u64,
+    pc_final: u64,
+    codelen: u64,
+    memory: impl Fn(u64) -> Option<u64>,
+) -> Result<(), VerifyError> {
+    if pc_init != PROGRAM_BASE {
+        return Err(VerifyError::VerificationFailed(format!(
+            "Execution starts at pc {}, not at the start of the program {}",
+            pc_init, PROGRAM_BASE
+        )));
+    }
+    // Both cells of the instruction are in the program
+    let program_end = PROGRAM_BASE.saturating_add(codelen);
+    let in_program =
+        pc_final >= PROGRAM_BASE && pc_final.checked_add(2).is_some_and(|end| end <= program_end);
+    if !in_program || memory(pc_final) != Some(JMP_REL_0) || memory(pc_final + 1) != Some(0) {
+        return Err(VerifyError::VerificationFailed(format!(
+            "Execution ends at pc {}, which is not the final loop of the program",
+            pc_final
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{check_entry_and_exit, JMP_REL_0, PROGRAM_BASE};
+
+    // ap += 1; call main; jmp rel 0; then main, which returns
+    const CODELEN: u64 = 7;
+    const END: u64 = 4;
+
+    fn memory() -> HashMap<u64, u64> {
+        HashMap::from([
+            (1, 0x40780017fff7fff),
+            (2, 1),
+            (3, 0x1104800180018000),
+            (4, JMP_REL_0),
+            (5, 0),
+            (6, 0x208b7fff7fff7ffe),
+            (7, JMP_REL_0),
+        ])
+    }
+
+    #[test]
+    fn test_entry_and_exit() {
+        let memory = memory();
+        let check = |pc_init, pc_final, codelen| {
+            check_entry_and_exit(pc_init, pc_final, codelen, |addr| memory.get(&addr).copied())
+        };
+        assert!(check(PROGRAM_BASE, END, CODELEN).is_ok());
+        // Started past __start__, or stopped anywhere but on a jmp rel 0
+        assert!(check(3, END, CODELEN).is_err());
+        assert!(check(0, END, CODELEN).is_err());
+        for pc_final in [0, 3, 5, 6, u64::MAX] {
+            assert!(check(PROGRAM_BASE, pc_final, CODELEN).is_err(), "pc_final {} accepted", pc_final);
+        }
+        // The last cell is a jmp rel 0 whose immediate is out of the program
+        assert!(check(PROGRAM_BASE, 7, CODELEN).is_err());
+        // The loop must be in the program, which is shorter than the memory read
+        assert!(check(PROGRAM_BASE, END, 4).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+//! Initial and final stacks of executions in proof mode, which locate the builtin segments.
+//!
+//! The runner starts with the base of each builtin of `main` on the stack, the output one
+//! first, and `main` returns their stop pointers:
+//! ```text
+//! [ap_init + i]         base of builtin i, for i < n
+//! [ap_init + n]         fp of the caller, which is ap_init, pushed by `call main`
+//! [ap_final - n + i]    stop pointer of builtin i
+//! ```
+//! `prove` makes these cells public, so that the verifier reads where the output segment is
+//! from the proven execution rather than from the segments given with the public inputs.
+
+use hyle_verifier_core::VerifyError;
+
+/// Programs have at most one of each builtin, far less than this.
+pub const MAX_BUILTINS: u64 = 32;
+
+/// Number of builtins of `main`, whose bases are on the initial stack up to the fp pushed by
+/// `call main`. Builtin bases are in their own segments, so none of them is `ap_init`.
+pub fn builtins_count(ap_init: u64, memory: impl Fn(u64) -> Option<u64>) -> Result<u64, VerifyError> {
+    for i in 0..=MAX_BUILTINS {
+        let addr = stack_addr(ap_init.checked_add(i))?;
+        if read(&memory, addr)? == ap_init {
+            return Ok(i);
+        }
+    }
+    Err(VerifyError::MalformedProof(format!(
+        "No call to main in the {} cells of the initial stack",
+        MAX_BUILTINS + 1
+    )))
+}
+
+/// Addresses of the cells of the initial and final stacks, for `builtins` builtins.
+pub fn stack_cells(ap_init: u64, ap_final: u64, builtins: u64) -> Result<Vec<u64>, VerifyError> {
+    let initial = stack_addr(ap_init.checked_add(builtins))?;
+    let last = stack_addr(ap_final.checked_sub(builtins))?;
+    Ok((ap_init..=initial).chain(last..ap_final).collect())
+}
+
+/// Output segment `[begin, stop)` of the execution, the one of its first builtin.
+pub fn output_segment(
+    ap_init: u64,
+    ap_final: u64,
+    memory: impl Fn(u64) -> Option<u64>,
+) -> Result<(u64, u64), VerifyError> {
+    let builtins = builtins_count(ap_init, &memory)?;
+    if builtins == 0 {
+        return Err(VerifyError::VerificationFailed(
+            "Program has no output builtin".to_string(),
+        ));
+    }
+    let begin = read(&memory, ap_init)?;
+    let stop = read(&memory, stack_addr(ap_final.checked_sub(builtins))?)?;
+    Ok((begin, stop))
+}
+
+/// Checks the output segment `[begin, stop)` given with the public inputs is the one of the
+/// execution, so that no other memory is read as its output.
+pub fn check_output_segment(
+    begin: u64,
+    stop: u64,
+    ap_init: u64,
+    ap_final: u64,
+    memory: impl Fn(u64) -> Option<u64>,
+) -> Result<(), VerifyError> {
+    let (proven_begin, proven_stop) = output_segment(ap_init, ap_final, memory)?;
+    if (begin, stop) != (proven_begin, proven_stop) {
+        return Err(VerifyError::VerificationFailed(format!(
+            "Output segment {}..{} is not the one of the execution, {}..{}",
+            begin, stop, proven_begin, proven_stop
+        )));
+    }
+    Ok(())
+}
+
+fn stack_addr(addr: Option<u64>) -> Result<u64, VerifyError> {
+    addr.ok_or_else(|| VerifyError::MalformedProof("Stack address out of range".to_string()))
+}
+
+fn read(memory: impl Fn(u64) -> Option<u64>, addr: u64) -> Result<u64, VerifyError> {
+    memory(addr).ok_or_else(|| {
+        VerifyError::MalformedProof(format!(
+            "Stack cell {} is missing from the public memory, or is not an address",
+            addr
+        ))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{builtins_count, check_output_segment, output_segment, stack_cells};
+
+    // Output and range check builtins, the output being 100..105
+    const AP_INIT: u64 = 20;
+    const AP_FINAL: u64 = 40;
+
+    fn memory() -> HashMap<u64, u64> {
+        HashMap::from([
+            (AP_INIT, 100),
+            (AP_INIT + 1, 200),
+            (AP_INIT + 2, AP_INIT),
+            (AP_INIT + 3, 7),
+            (AP_FINAL - 2, 105),
+            (AP_FINAL - 1, 210),
+        ])
+    }
+
+    #[test]
+    fn test_output_segment() {
+        let memory = memory();
+        assert_eq!(builtins_count(AP_INIT, |addr| memory.get(&addr).copied()).unwrap(), 2);
+        assert_eq!(
+            output_segment(AP_INIT, AP_FINAL, |addr| memory.get(&addr).copied()).unwrap(),
+            (100, 105)
+        );
+        let mut cells = stack_cells(AP_INIT, AP_FINAL, 2).unwrap();
+        cells.sort();
+        let mut expected: Vec<u64> = memory.keys().copied().filter(|addr| *addr != AP_INIT + 3).collect();
+        expected.sort();
+        assert_eq!(cells, expected);
+    }
+
+    #[test]
+    fn test_tampered_output_segment() {
+        let memory = memory();
+        let check = |begin, stop| {
+            check_output_segment(begin, stop, AP_INIT, AP_FINAL, |addr| memory.get(&addr).copied())
+        };
+        assert!(check(100, 105).is_ok());
+        // Extended past the output, shortened, or moved to other memory
+        for (begin, stop) in [(100, 106), (100, 104), (99, 105), (200, 210)] {
+            assert!(check(begin, stop).is_err(), "{}..{} accepted", begin, stop);
+        }
+    }
+
+    #[test]
+    fn test_missing_cells() {
+        for addr in [AP_INIT, AP_INIT + 1, AP_INIT + 2, AP_FINAL - 2] {
+            let mut memory = memory();
+            memory.remove(&addr);
+            assert!(output_segment(AP_INIT, AP_FINAL, |addr| memory.get(&addr).copied()).is_err());
+        }
+        // Without the output builtin
+        let memory = HashMap::from([(AP_INIT, AP_INIT)]);
+        assert!(output_segment(AP_INIT, AP_FINAL, |addr| memory.get(&addr).copied()).is_err());
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt;
+
+use serde::Serialize;
+
+pub use hyle_contract::HyleOutput;
+
+pub mod schema;
+
+/// This is the interface every proof system backend implements, so that they can be
+/// linked together and used in-process instead of spawning one binary per proof.
+pub trait Verifier: Send + Sync {
+    /// Short name of the proof system, e.g. "risc0".
+    fn name(&self) -> &'static str;
+
+    /// Verifies `proof` against the program identified by `program_id`
+    /// (image id, program hash, verification key... depending on the backend).
+    /// `extra_inputs` holds the public inputs some backends need besides the proof,
+    /// and is `null` for the others.
+    fn verify(
+        &self,
+        program_id: &str,
+        proof: &[u8],
+        extra_inputs: &serde_json::Value,
+    ) -> Result<HyleOutput<serde_json::Value>, VerifyError>;
+}
+
+/// Errors shared by all verifiers.
+/// They are printed as JSON on stderr, e.g. `{"error":"malformed_proof","message":"..."}`,
+/// and each kind exits with its own code so callers can tell a bad proof from a broken verifier.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "error", content = "message", rename_all = "snake_case")]
+pub enum VerifyError {
+    /// The proof, or its extra inputs, could not be decoded.
+    MalformedProof(String),
+    /// The program identifier could not be parsed, or the proof is for another program.
+    WrongProgramId(String),
+    /// The proof does not verify.
+    VerificationFailed(String),
+    /// The proof verifies but its output is not a valid HyleOutput.
+    OutputDecode(String),
+    /// A file could not be read or written.
+    Io(String),
+    /// The verifier itself failed, whatever the proof.
+    Internal(String),
+}
+
+/// Exit code of a successful verification.
+pub const EXIT_SUCCESS: i32 = 0;
+/// Exit code when some of several verifications failed, each one reporting its own error.
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit code when the command line arguments are invalid, as used by clap.
+pub const EXIT_USAGE: i32 = 2;
+
+impl VerifyError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            VerifyError::MalformedProof(_) => 3,
+            VerifyError::WrongProgramId(_) => 4,
+            VerifyError::VerificationFailed(_) => 5,
+            VerifyError::OutputDecode(_) => 6,
+            VerifyError::Io(_) => 7,
+            VerifyError::Internal(_) => 8,
+        }
+    }
+
+    /// Whether the error comes from the proof rather than from the verifier.
+    pub fn is_proof_error(&self) -> bool {
+        !matches!(self, VerifyError::Io(_) | VerifyError::Internal(_))
+    }
+
+    /// Prints the error as JSON on stderr and exits with its code.
+    pub fn exit(&self) -> ! {
+        eprintln!("{}", serde_json::to_string(self).expect("Failed to serialize error"));
+        std::process::exit(self.exit_code());
+    }
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::MalformedProof(msg) => write!(f, "Malformed proof: {}", msg),
+            VerifyError::WrongProgramId(msg) => write!(f, "Wrong program id: {}", msg),
+            VerifyError::VerificationFailed(msg) => write!(f, "Verification failed: {}", msg),
+            VerifyError::OutputDecode(msg) => write!(f, "Failed to decode output: {}", msg),
+            VerifyError::Io(msg) => write!(f, "I/O error: {}", msg),
+            VerifyError::Internal(msg) => write!(f, "Internal error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Reads a file, reporting failures as [VerifyError::Io].
+pub fn read_file(path: impl AsRef<std::path::Path>) -> Result<Vec<u8>, VerifyError> {
+    let path = path.as_ref();
+    std::fs::read(path).map_err(|err| VerifyError::Io(format!("Failed to read {}: {}", path.display(), err)))
+}
+
+/// Reads an optional string of the extra inputs of a backend. A value of another type, null
+/// included, is rejected rather than ignored, so that a misspelt option is not dropped silently.
+pub fn extra_input_str<'a>(
+    extra_inputs: &'a serde_json::Value,
+    key: &str,
+) -> Result<Option<&'a str>, VerifyError> {
+    match extra_inputs.get(key) {
+        None => Ok(None),
+        Some(serde_json::Value::String(value)) => Ok(Some(value)),
+        Some(value) => Err(VerifyError::MalformedProof(format!(
+            "Invalid {}: expected a string, got {}",
+            key, value
+        ))),
+    }
+}
+
+/// Converts the typed program outputs of a backend to JSON.
+pub fn to_json_output<T: Serialize>(
+    output: HyleOutput<T>,
+) -> Result<HyleOutput<serde_json::Value>, VerifyError> {
+    let program_outputs = serde_json::to_value(&output.program_outputs)
+        .map_err(|err| VerifyError::Internal(err.to_string()))?;
+    Ok(HyleOutput {
+        version: output.version,
+        initial_state: output.initial_state,
+        next_state: output.next_state,
+        origin: output.origin,
+        caller: output.caller,
+        block_number: output.block_number,
+        block_time: output.block_time,
+        tx_hash: output.tx_hash,
+        program_outputs,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{extra_input_str, VerifyError};
+
+    #[test]
+    fn test_extra_input_str() {
+        let extra_inputs = serde_json::json!({ "vk_hash": "00ff" });
+        assert_eq!(extra_input_str(&extra_inputs, "vk_hash").unwrap(), Some("00ff"));
+        assert_eq!(extra_input_str(&extra_inputs, "outputs_schema").unwrap(), None);
+        assert_eq!(extra_input_str(&serde_json::Value::Null, "vk_hash").unwrap(), None);
+        for value in [serde_json::json!(1), serde_json::Value::Null, serde_json::json!(["00ff"])] {
+            let extra_inputs = serde_json::json!({ "vk_hash": value });
+            let err = extra_input_str(&extra_inputs, "vk_hash").unwrap_err();
+            assert!(matches!(err, VerifyError::MalformedProof(_)), "{:?}", err);
+        }
+    }
+
+    #[test]
+    fn test_error_json_and_exit_codes() {
+        let err = VerifyError::WrongProgramId("expected 0x1".to_string());
+        assert_eq!(
+            serde_json::to_string(&err).unwrap(),
+            r#"{"error":"wrong_program_id","message":"expected 0x1"}"#
+        );
+
+        let errors = [
+            VerifyError::MalformedProof(String::new()),
+            VerifyError::WrongProgramId(String::new()),
+            VerifyError::VerificationFailed(String::new()),
+            VerifyError::OutputDecode(String::new()),
+            VerifyError::Io(String::new()),
+            VerifyError::Internal(String::new()),
+        ];
+        let mut codes: Vec<i32> = errors.iter().map(VerifyError::exit_code).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len());
+        assert!(codes.iter().all(|code| *code > super::EXIT_USAGE));
+    }
+}
@@ -0,0 +1,322 @@
+//! Program outputs whose layout is only known at runtime, decoded from any serde format
+//! encoding structs as sequences of fields, like risc0 journals and SP1 public values.
+
+use std::fmt;
+use std::str::FromStr;
+
+use hyle_contract::HyleOutput;
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer, SeqAccess, Visitor};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Primitive types a field of the program outputs can have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+    String,
+    Bytes,
+}
+
+impl FromStr for FieldType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "bool" => FieldType::Bool,
+            "u8" => FieldType::U8,
+            "u16" => FieldType::U16,
+            "u32" => FieldType::U32,
+            "u64" => FieldType::U64,
+            "i8" => FieldType::I8,
+            "i16" => FieldType::I16,
+            "i32" => FieldType::I32,
+            "i64" => FieldType::I64,
+            "string" => FieldType::String,
+            "bytes" => FieldType::Bytes,
+            _ => return Err(format!("unknown field type '{}'", s)),
+        })
+    }
+}
+
+/// Layout of the program outputs committed after the HyleOutput fields, as a list of
+/// `name:type` separated by commas, e.g. `from:string,to:string,amount:u64`.
+/// Fields are decoded in order, as a struct or a tuple of these types would be.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schema(pub Vec<(String, FieldType)>);
+
+impl FromStr for Schema {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .filter(|field| !field.trim().is_empty())
+            .map(|field| {
+                let Some((name, field_type)) = field.split_once(':') else {
+                    return Err(format!("expected name:type, got '{}'", field.trim()));
+                };
+                Ok((name.trim().to_string(), field_type.trim().parse()?))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Schema)
+    }
+}
+
+/// Deserializes an HyleOutput whose program outputs follow the schema, as JSON.
+pub fn decode_with_schema<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    schema: &Schema,
+) -> Result<HyleOutput<Value>, D::Error> {
+    HyleOutputSeed(schema).deserialize(deserializer)
+}
+
+/// Result of decoding one field of an HyleOutput.
+#[derive(Serialize, Debug)]
+pub struct FieldDecode {
+    pub field: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Decodes the fields of an HyleOutput one by one, up to the first one that fails, to show
+/// where a journal diverges from the layout. The program outputs are only decoded with a schema.
+pub fn decode_fields<'de, D>(deserializer: &mut D, schema: Option<&Schema>) -> Vec<FieldDecode>
+where
+    for<'a> &'a mut D: Deserializer<'de>,
+{
+    fn decode<'de, T, D>(field: &'static str, deserializer: &mut D) -> FieldDecode
+    where
+        T: Deserialize<'de> + Into<Value>,
+        for<'a> &'a mut D: Deserializer<'de>,
+    {
+        match T::deserialize(deserializer) {
+            Ok(value) => FieldDecode { field, value: Some(value.into()), error: None },
+            Err(err) => FieldDecode { field, value: None, error: Some(err.to_string()) },
+        }
+    }
+
+    let decoders: [fn(&mut D) -> FieldDecode; 8] = [
+        |d| decode::<u32, D>("version", d),
+        |d| decode::<Vec<u8>, D>("initial_state", d),
+        |d| decode::<Vec<u8>, D>("next_state", d),
+        |d| decode::<String, D>("origin", d),
+        |d| decode::<String, D>("caller", d),
+        |d| decode::<u64, D>("block_number", d),
+        |d| decode::<u64, D>("block_time", d),
+        |d| decode::<Vec<u8>, D>("tx_hash", d),
+    ];
+    let mut fields = vec![];
+    for decoder in decoders {
+        let field = decoder(deserializer);
+        let failed = field.error.is_some();
+        fields.push(field);
+        if failed {
+            return fields;
+        }
+    }
+    if let Some(schema) = schema {
+        fields.push(match ProgramOutputsSeed(schema).deserialize(deserializer) {
+            Ok(value) => FieldDecode { field: "program_outputs", value: Some(value), error: None },
+            Err(err) => FieldDecode { field: "program_outputs", value: None, error: Some(err.to_string()) },
+        });
+    }
+    fields
+}
+
+const HYLE_OUTPUT_FIELDS: &[&str] = &[
+    "version",
+    "initial_state",
+    "next_state",
+    "origin",
+    "caller",
+    "block_number",
+    "block_time",
+    "tx_hash",
+    "program_outputs",
+];
+
+struct HyleOutputSeed<'a>(&'a Schema);
+
+impl<'de, 'a> DeserializeSeed<'de> for HyleOutputSeed<'a> {
+    type Value = HyleOutput<Value>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_struct("HyleOutput", HYLE_OUTPUT_FIELDS, self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for HyleOutputSeed<'a> {
+    type Value = HyleOutput<Value>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an HyleOutput")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let field = |i: usize| de::Error::invalid_length(i, &"the 9 fields of an HyleOutput");
+        Ok(HyleOutput {
+            version: seq.next_element()?.ok_or_else(|| field(0))?,
+            initial_state: seq.next_element()?.ok_or_else(|| field(1))?,
+            next_state: seq.next_element()?.ok_or_else(|| field(2))?,
+            origin: seq.next_element()?.ok_or_else(|| field(3))?,
+            caller: seq.next_element()?.ok_or_else(|| field(4))?,
+            block_number: seq.next_element()?.ok_or_else(|| field(5))?,
+            block_time: seq.next_element()?.ok_or_else(|| field(6))?,
+            tx_hash: seq.next_element()?.ok_or_else(|| field(7))?,
+            program_outputs: seq
+                .next_element_seed(ProgramOutputsSeed(self.0))?
+                .ok_or_else(|| field(8))?,
+        })
+    }
+}
+
+struct ProgramOutputsSeed<'a>(&'a Schema);
+
+impl<'de, 'a> DeserializeSeed<'de> for ProgramOutputsSeed<'a> {
+    type Value = Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_tuple(self.0 .0.len(), self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for ProgramOutputsSeed<'a> {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} program output fields", self.0 .0.len())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut outputs = Map::new();
+        for (i, (name, field_type)) in self.0 .0.iter().enumerate() {
+            let missing = || de::Error::invalid_length(i, &self);
+            let value = match field_type {
+                FieldType::Bool => seq.next_element::<bool>()?.map(Value::from),
+                FieldType::U8 => seq.next_element::<u8>()?.map(Value::from),
+                FieldType::U16 => seq.next_element::<u16>()?.map(Value::from),
+                FieldType::U32 => seq.next_element::<u32>()?.map(Value::from),
+                FieldType::U64 => seq.next_element::<u64>()?.map(Value::from),
+                FieldType::I8 => seq.next_element::<i8>()?.map(Value::from),
+                FieldType::I16 => seq.next_element::<i16>()?.map(Value::from),
+                FieldType::I32 => seq.next_element::<i32>()?.map(Value::from),
+                FieldType::I64 => seq.next_element::<i64>()?.map(Value::from),
+                FieldType::String => seq.next_element::<String>()?.map(Value::from),
+                FieldType::Bytes => seq.next_element::<Vec<u8>>()?.map(Value::from),
+            };
+            outputs.insert(name.clone(), value.ok_or_else(missing)?);
+        }
+        Ok(Value::Object(outputs))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bincode::Options;
+    use hyle_contract::HyleOutput;
+    use serde::Serialize;
+
+    use super::{decode_fields, decode_with_schema, FieldType, Schema};
+
+    #[derive(Serialize)]
+    struct Transfer {
+        from: String,
+        to: String,
+        amount: u64,
+        memo: Vec<u8>,
+    }
+
+    #[test]
+    fn test_parse_schema() {
+        let schema: Schema = "from:string, to:string,amount:u64".parse().unwrap();
+        assert_eq!(
+            schema.0,
+            vec![
+                ("from".to_string(), FieldType::String),
+                ("to".to_string(), FieldType::String),
+                ("amount".to_string(), FieldType::U64),
+            ]
+        );
+        assert!("amount".parse::<Schema>().is_err());
+        assert!("amount:u128".parse::<Schema>().is_err());
+    }
+
+    #[test]
+    fn test_decode_with_schema() {
+        let output = HyleOutput {
+            version: 1,
+            initial_state: vec![1, 2],
+            next_state: vec![3],
+            origin: "alice".to_string(),
+            caller: "bob".to_string(),
+            block_number: 4,
+            block_time: 5,
+            tx_hash: vec![6],
+            program_outputs: Transfer {
+                from: "alice".to_string(),
+                to: "carol".to_string(),
+                amount: 10,
+                memo: vec![7, 8],
+            },
+        };
+        // bincode encodes structs as sequences of fields, like risc0 journals
+        let bytes = bincode::serialize(&output).unwrap();
+        let schema = "from:string,to:string,amount:u64,memo:bytes".parse().unwrap();
+
+        let mut deserializer = bincode::Deserializer::from_slice(&bytes, bincode::DefaultOptions::new().with_fixint_encoding());
+        let decoded = decode_with_schema(&mut deserializer, &schema).unwrap();
+        assert_eq!(decoded.origin, "alice");
+        assert_eq!(decoded.tx_hash, vec![6]);
+        assert_eq!(
+            decoded.program_outputs,
+            serde_json::json!({"from": "alice", "to": "carol", "amount": 10, "memo": [7, 8]})
+        );
+
+        // A schema longer than the committed outputs fails
+        let schema = "from:string,to:string,amount:u64,memo:bytes,extra:u32".parse().unwrap();
+        let mut deserializer = bincode::Deserializer::from_slice(&bytes, bincode::DefaultOptions::new().with_fixint_encoding());
+        assert!(decode_with_schema(&mut deserializer, &schema).is_err());
+    }
+
+    #[test]
+    fn test_decode_fields() {
+        let output = HyleOutput {
+            version: 1,
+            initial_state: vec![1, 2],
+            next_state: vec![3],
+            origin: "alice".to_string(),
+            caller: "bob".to_string(),
+            block_number: 4,
+            block_time: 5,
+            tx_hash: vec![6; 32],
+            program_outputs: 7u32,
+        };
+        let bytes = bincode::serialize(&output).unwrap();
+        let options = || bincode::DefaultOptions::new().with_fixint_encoding();
+
+        let schema = "amount:u32".parse().unwrap();
+        let mut deserializer = bincode::Deserializer::from_slice(&bytes, options());
+        let fields = decode_fields(&mut deserializer, Some(&schema));
+        assert_eq!(fields.len(), 9);
+        assert!(fields.iter().all(|field| field.error.is_none()));
+        assert_eq!(fields[3].value, Some(serde_json::json!("alice")));
+        assert_eq!(fields[8].value, Some(serde_json::json!({"amount": 7})));
+
+        // Decoding stops at the first field cut short
+        let mut deserializer = bincode::Deserializer::from_slice(&bytes[..bytes.len() - 10], options());
+        let fields = decode_fields(&mut deserializer, None);
+        assert_eq!(fields.len(), 8);
+        assert_eq!(fields[7].field, "tx_hash");
+        assert!(fields[7].error.is_some());
+        assert!(fields[..7].iter().all(|field| field.error.is_none()));
+    }
+}
@@ -0,0 +1,184 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+
+use clap::ValueEnum;
+use hyle_contract::HyleOutput;
+use hyle_verifier_core::{Verifier, VerifyError};
+use serde::{Deserialize, Serialize};
+
+/// Proof systems supported by this binary.
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    Risc0,
+    Sp1,
+    Cairo,
+    Miden,
+}
+
+impl Backend {
+    /// Returns the verifier for this proof system, if it was compiled in.
+    pub fn verifier(self) -> Option<&'static dyn Verifier> {
+        match self {
+            Backend::Risc0 => Some(&risc0_verifier::Risc0Verifier),
+            #[cfg(feature = "sp1")]
+            Backend::Sp1 => Some(&sp1_verifier::Sp1Verifier),
+            #[cfg(not(feature = "sp1"))]
+            Backend::Sp1 => None,
+            Backend::Cairo => Some(&cairo_verifier::CairoVerifier),
+            Backend::Miden => Some(&midenvm_verifier::MidenVerifier),
+        }
+    }
+
+    /// Guesses the proof system from the proof content.
+    /// - RISC Zero receipts are JSON objects with a journal,
+    /// - Miden is the only proof system needing stack inputs and outputs,
+    /// - Cairo proofs start with their magic bytes,
+    /// - binary RISC Zero receipts are those that deserialize as such,
+    /// - older Cairo proofs are length prefixed proof and public inputs, followed by the
+    ///   output, and are detected to be rejected by the Cairo verifier. As bincode receipts
+    ///   can look like this, they are checked first,
+    /// - anything else is assumed to be a SP1 proof.
+    pub fn detect(proof: &[u8], has_stack: bool) -> Backend {
+        if has_stack {
+            return Backend::Miden;
+        }
+        if let Ok(serde_json::Value::Object(receipt)) = serde_json::from_slice(proof) {
+            if receipt.contains_key("journal") {
+                return Backend::Risc0;
+            }
+        }
+        if proof.starts_with(cairo_verifier::utils::container::MAGIC) {
+            return Backend::Cairo;
+        }
+        if risc0_verifier::parse_any_receipt(proof, risc0_verifier::ReceiptFormat::Bincode, None).is_ok() {
+            return Backend::Risc0;
+        }
+        if is_legacy_cairo_proof(proof) {
+            return Backend::Cairo;
+        }
+        Backend::Sp1
+    }
+}
+
+/// Verifies a proof with the given backend, or the detected one when `None`.
+pub fn verify(
+    backend: Option<Backend>,
+    program_id: &str,
+    proof: &[u8],
+    extra_inputs: &serde_json::Value,
+) -> Result<HyleOutput<serde_json::Value>, VerifyError> {
+    let has_stack = extra_inputs.get("stack_inputs").is_some();
+    let backend = backend.unwrap_or_else(|| Backend::detect(proof, has_stack));
+    let Some(verifier) = backend.verifier() else {
+        return Err(VerifyError::Internal(format!(
+            "{:?} support was not compiled in this verifier",
+            backend
+        )));
+    };
+    verifier.verify(program_id, proof, extra_inputs)
+}
+
+/// Runs a verification, turning a panic of a verifier into an error so that `serve` and
+/// `batch` carry on with the other proofs.
+pub fn catch_panic(
+    verify: impl FnOnce() -> Result<HyleOutput<serde_json::Value>, VerifyError>,
+) -> Result<HyleOutput<serde_json::Value>, VerifyError> {
+    panic::catch_unwind(AssertUnwindSafe(verify)).unwrap_or_else(|payload| {
+        Err(VerifyError::Internal(format!(
+            "Verifier panicked: {}",
+            panic_message(payload.as_ref())
+        )))
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
+/// Result of a verification as reported by `serve` and `batch`:
+/// either `{"output": ...}` or `{"error": "<kind>", "message": ...}`.
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+pub enum Outcome {
+    Output {
+        output: HyleOutput<serde_json::Value>,
+    },
+    Error(VerifyError),
+}
+
+impl Outcome {
+    pub fn is_error(&self) -> bool {
+        matches!(self, Outcome::Error(_))
+    }
+}
+
+impl From<Result<HyleOutput<serde_json::Value>, VerifyError>> for Outcome {
+    fn from(result: Result<HyleOutput<serde_json::Value>, VerifyError>) -> Self {
+        match result {
+            Ok(output) => Outcome::Output { output },
+            Err(err) => Outcome::Error(err),
+        }
+    }
+}
+
+fn is_legacy_cairo_proof(bytes: &[u8]) -> bool {
+    let read_len = |at: usize| {
+        bytes
+            .get(at..at + 4)
+            .map(|len| u32::from_le_bytes(len.try_into().unwrap()) as usize)
+    };
+    let Some(proof_len) = read_len(0) else {
+        return false;
+    };
+    let Some(pub_inputs_len) = read_len(4 + proof_len) else {
+        return false;
+    };
+    8 + proof_len + pub_inputs_len < bytes.len()
+}
+
+#[cfg(test)]
+mod test {
+    use risc0_zkvm::sha::Digest;
+    use risc0_zkvm::{
+        Assumptions, ExitCode, InnerReceipt, MaybePruned, Output, Receipt, ReceiptClaim,
+    };
+
+    use super::Backend;
+
+    fn fake_receipt(journal: &[u8]) -> Receipt {
+        let claim = ReceiptClaim {
+            pre: MaybePruned::Pruned(Digest::ZERO),
+            post: MaybePruned::Pruned(Digest::ZERO),
+            exit_code: ExitCode::Halted(0),
+            input: Digest::ZERO,
+            output: MaybePruned::Value(Some(Output {
+                journal: MaybePruned::Value(journal.to_vec()),
+                assumptions: MaybePruned::Value(Assumptions(vec![])),
+            })),
+        };
+        Receipt::new(InnerReceipt::Fake { claim }, journal.to_vec())
+    }
+
+    #[test]
+    fn test_detect() {
+        let receipt = fake_receipt(&[1, 0, 0, 0]);
+        let json = serde_json::to_vec(&receipt).unwrap();
+        assert_eq!(Backend::detect(&json, false), Backend::Risc0);
+        // Small enum tags and zero digests can pass for the lengths of a legacy Cairo proof
+        let bincode = bincode::serialize(&receipt).unwrap();
+        assert_eq!(Backend::detect(&bincode, false), Backend::Risc0);
+
+        let mut cairo = cairo_verifier::utils::container::MAGIC.to_vec();
+        cairo.extend_from_slice(&[0; 16]);
+        assert_eq!(Backend::detect(&cairo, false), Backend::Cairo);
+        assert_eq!(Backend::detect(&cairo, true), Backend::Miden);
+        assert_eq!(Backend::detect(&[0xff; 16], false), Backend::Sp1);
+    }
+}
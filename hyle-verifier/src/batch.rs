@@ -0,0 +1,275 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use hyle_contract::HyleOutput;
+use hyle_verifier_core::{read_file, VerifyError, EXIT_FAILURE, EXIT_SUCCESS};
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{self, Backend, Outcome};
+
+/// List of proofs to verify, as JSON or TOML (`[[proofs]]` tables).
+#[derive(Deserialize, Debug)]
+pub struct Manifest {
+    pub proofs: Vec<ManifestEntry>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ManifestEntry {
+    /// Proof system of the proof, detected from the proof when omitted.
+    pub backend: Option<Backend>,
+    pub program_id: String,
+    /// Paths are relative to the manifest.
+    pub proof_path: String,
+    /// Stack inputs and outputs files for Miden proofs.
+    pub stack_inputs: Option<String>,
+    pub stack_outputs: Option<String>,
+    /// Layout of the program outputs of RISC Zero proofs, e.g. `from:string,amount:u64`.
+    pub outputs_schema: Option<String>,
+    /// Fields the HyleOutput must have, e.g. `next_state` or `tx_hash`.
+    #[serde(default)]
+    pub expected: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Report {
+    pub passed: usize,
+    pub failed: usize,
+    pub results: Vec<EntryResult>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct EntryResult {
+    pub proof_path: String,
+    #[serde(flatten)]
+    pub result: Outcome,
+}
+
+impl Report {
+    /// Exit code of `batch`, a failure when any proof failed.
+    pub fn exit_code(&self) -> i32 {
+        if self.failed > 0 {
+            EXIT_FAILURE
+        } else {
+            EXIT_SUCCESS
+        }
+    }
+}
+
+type VerifyResult = Result<HyleOutput<serde_json::Value>, VerifyError>;
+
+/// Signature of [backend::verify], which tests replace.
+type VerifyFn = dyn Fn(Option<Backend>, &str, &[u8], &serde_json::Value) -> VerifyResult + Sync;
+
+/// Verifies every proof of the manifest, `jobs` at a time.
+pub fn run(manifest_path: &str, jobs: Option<usize>) -> Result<Report, VerifyError> {
+    run_with(manifest_path, jobs, &backend::verify)
+}
+
+fn run_with(
+    manifest_path: &str,
+    jobs: Option<usize>,
+    verify: &VerifyFn,
+) -> Result<Report, VerifyError> {
+    let manifest = read_manifest(manifest_path)?;
+    let base_dir = Path::new(manifest_path).parent().unwrap_or(Path::new(""));
+    let jobs = jobs
+        .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get()))
+        .unwrap_or(1)
+        .clamp(1, manifest.proofs.len().max(1));
+
+    // Workers pick the next entry until there are none left
+    let next = AtomicUsize::new(0);
+    let results: Vec<Mutex<Option<EntryResult>>> =
+        manifest.proofs.iter().map(|_| Mutex::new(None)).collect();
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(entry) = manifest.proofs.get(i) else {
+                    break;
+                };
+                *results[i].lock().unwrap() = Some(verify_entry(base_dir, entry, verify));
+            });
+        }
+    });
+
+    let results: Vec<EntryResult> = results
+        .into_iter()
+        .map(|result| result.into_inner().unwrap().expect("every entry is verified"))
+        .collect();
+    let failed = results.iter().filter(|entry| entry.result.is_error()).count();
+    Ok(Report {
+        passed: results.len() - failed,
+        failed,
+        results,
+    })
+}
+
+fn read_manifest(path: &str) -> Result<Manifest, VerifyError> {
+    let content = read_file(path)?;
+    let manifest = if path.ends_with(".toml") {
+        String::from_utf8(content)
+            .map_err(|err| err.to_string())
+            .and_then(|content| toml::from_str(&content).map_err(|err| err.to_string()))
+    } else {
+        serde_json::from_slice(&content).map_err(|err| err.to_string())
+    };
+    manifest.map_err(|err| VerifyError::MalformedProof(format!("Invalid manifest {}: {}", path, err)))
+}
+
+/// Verifies an entry, a panic of its verifier failing this entry only.
+fn verify_entry(base_dir: &Path, entry: &ManifestEntry, verify: &VerifyFn) -> EntryResult {
+    EntryResult {
+        proof_path: entry.proof_path.clone(),
+        result: backend::catch_panic(|| check_entry(base_dir, entry, verify)).into(),
+    }
+}
+
+fn check_entry(
+    base_dir: &Path,
+    entry: &ManifestEntry,
+    verify: &VerifyFn,
+) -> VerifyResult {
+    let read_json = |path: &str| -> Result<serde_json::Value, VerifyError> {
+        serde_json::from_slice(&read_file(base_dir.join(path))?)
+            .map_err(|err| VerifyError::MalformedProof(format!("Failed to parse {}: {}", path, err)))
+    };
+
+    let proof = read_file(base_dir.join(&entry.proof_path))?;
+    let extra_inputs = match (&entry.stack_inputs, &entry.stack_outputs) {
+        (Some(inputs), Some(outputs)) => serde_json::json!({
+            "stack_inputs": read_json(inputs)?,
+            "stack_outputs": read_json(outputs)?,
+        }),
+        (None, None) => match &entry.outputs_schema {
+            Some(schema) => serde_json::json!({ "outputs_schema": schema }),
+            None => serde_json::Value::Null,
+        },
+        _ => {
+            return Err(VerifyError::MalformedProof(
+                "stack_inputs and stack_outputs go together".to_string(),
+            ))
+        }
+    };
+    let output = verify(entry.backend, &entry.program_id, &proof, &extra_inputs)?;
+
+    let actual = serde_json::to_value(&output).map_err(|err| VerifyError::Internal(err.to_string()))?;
+    for (field, expected) in &entry.expected {
+        match actual.get(field) {
+            Some(value) if value == expected => {}
+            value => {
+                return Err(VerifyError::VerificationFailed(format!(
+                    "Unexpected {}: expected {}, got {}",
+                    field,
+                    expected,
+                    value.unwrap_or(&serde_json::Value::Null)
+                )))
+            }
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use hyle_contract::HyleOutput;
+    use hyle_verifier_core::{VerifyError, EXIT_FAILURE, EXIT_SUCCESS};
+
+    use super::{run_with, Backend, VerifyResult};
+
+    /// Pretends every proof is valid, with the proof as its next state.
+    fn verify(_: Option<Backend>, program_id: &str, proof: &[u8], _: &serde_json::Value) -> VerifyResult {
+        if program_id == "panic" {
+            panic!("backend failure");
+        }
+        Ok(HyleOutput {
+            version: 1,
+            initial_state: vec![],
+            next_state: proof.to_vec(),
+            origin: String::new(),
+            caller: String::new(),
+            block_number: 0,
+            block_time: 0,
+            tx_hash: vec![],
+            program_outputs: serde_json::Value::Null,
+        })
+    }
+
+    /// Writes the manifest and the proofs it lists in a directory of their own.
+    fn write_manifest(name: &str, manifest: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("hyle-batch-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(dir.join("proofs")).unwrap();
+        std::fs::write(dir.join("proofs/a.proof"), [1]).unwrap();
+        std::fs::write(dir.join("proofs/b.proof"), [2]).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, manifest).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_json_manifest() {
+        let path = write_manifest(
+            "manifest.json",
+            r#"{"proofs": [
+                {"backend": "risc0", "program_id": "0x1", "proof_path": "proofs/a.proof", "expected": {"next_state": [1]}},
+                {"program_id": "0x2", "proof_path": "proofs/b.proof", "expected": {"next_state": [1]}}
+            ]}"#,
+        );
+        let report = run_with(path.to_str().unwrap(), Some(2), &verify).unwrap();
+        assert_eq!((report.passed, report.failed), (1, 1));
+        assert_eq!(report.results[0].proof_path, "proofs/a.proof");
+        assert!(!report.results[0].result.is_error());
+        // The second proof does not have the expected next state
+        let failure = serde_json::to_value(&report.results[1]).unwrap();
+        assert_eq!(failure["error"], "verification_failed");
+        assert_eq!(report.exit_code(), EXIT_FAILURE);
+    }
+
+    #[test]
+    fn test_toml_manifest() {
+        let path = write_manifest(
+            "manifest.toml",
+            r#"
+            [[proofs]]
+            program_id = "0x1"
+            proof_path = "proofs/a.proof"
+
+            [[proofs]]
+            backend = "cairo"
+            program_id = "0x2"
+            proof_path = "proofs/b.proof"
+            expected = { next_state = [2] }
+            "#,
+        );
+        let report = run_with(path.to_str().unwrap(), None, &verify).unwrap();
+        assert_eq!((report.passed, report.failed), (2, 0));
+        assert_eq!(report.exit_code(), EXIT_SUCCESS);
+    }
+
+    #[test]
+    fn test_failing_entries() {
+        let path = write_manifest(
+            "failing.json",
+            r#"{"proofs": [
+                {"program_id": "panic", "proof_path": "proofs/a.proof"},
+                {"program_id": "0x1", "proof_path": "proofs/missing.proof"},
+                {"program_id": "0x1", "proof_path": "proofs/b.proof"}
+            ]}"#,
+        );
+        let report = run_with(path.to_str().unwrap(), Some(1), &verify).unwrap();
+        assert_eq!((report.passed, report.failed), (1, 2));
+        let errors: Vec<serde_json::Value> = report
+            .results
+            .iter()
+            .map(|entry| serde_json::to_value(entry).unwrap()["error"].clone())
+            .collect();
+        assert_eq!(errors, [serde_json::json!("internal"), serde_json::json!("io"), serde_json::Value::Null]);
+
+        let path = write_manifest("invalid.json", r#"{"proofs": [{"proof_path": "proofs/a.proof"}]}"#);
+        let err = run_with(path.to_str().unwrap(), None, &verify).unwrap_err();
+        assert!(matches!(err, VerifyError::MalformedProof(_)), "{:?}", err);
+    }
+}
@@ -0,0 +1,106 @@
+use cairo_verifier::utils::options::Security;
+use clap::{Args, Parser, Subcommand};
+
+#[derive(Subcommand, Debug)]
+pub enum VerifierEntity {
+    #[clap(about = "Verify a RISC Zero receipt for a given image id")]
+    Risc0(Risc0Args),
+    #[cfg(feature = "sp1")]
+    #[clap(about = "Verify a SP1 proof for a given verification key")]
+    Sp1(Sp1Args),
+    #[clap(about = "Verify a Cairo proof for a given program hash")]
+    Cairo(CairoArgs),
+    #[clap(about = "Verify a Miden proof for a given program hash and stack inputs and outputs")]
+    Miden(MidenArgs),
+    #[clap(about = "Detect the proof system from the proof and verify it")]
+    Auto(AutoArgs),
+    #[clap(about = "Verify all the proofs listed in a JSON or TOML manifest")]
+    Batch(BatchArgs),
+    #[clap(about = "Verify proofs sent as JSON lines on stdin, or on a unix socket")]
+    Serve(ServeArgs),
+}
+
+#[cfg(feature = "sp1")]
+#[derive(Args, Debug)]
+pub struct Sp1Args {
+    /// Verification key, base64 encoded JSON or the path of a file holding it
+    pub program_id: String,
+    pub proof_path: String,
+    /// Refuse the proof unless the verification key has this hash, as printed in vk_hash
+    #[clap(long)]
+    pub vk_hash: Option<String>,
+    /// Decode the program outputs as these fields, e.g. "from:string,to:string,amount:u64"
+    #[clap(long)]
+    pub outputs_schema: Option<String>,
+    /// Only accept proofs of these modes: core, compressed, plonk or groth16
+    #[clap(long, value_delimiter = ',')]
+    pub accept_modes: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct CairoArgs {
+    pub program_id: String,
+    pub proof_path: String,
+    /// Reject proofs targeting a lower security level, conjecturable100 by default
+    #[clap(long, value_enum)]
+    pub min_security: Option<Security>,
+}
+
+#[derive(Args, Debug)]
+pub struct Risc0Args {
+    pub program_id: String,
+    pub proof_path: String,
+    /// Decode the program outputs as these fields, e.g. "from:string,to:string,amount:u64"
+    #[clap(long)]
+    pub outputs_schema: Option<String>,
+    /// Receipt resolving an assumption of the receipt, can be repeated
+    #[clap(long = "assumption")]
+    pub assumptions: Vec<String>,
+    /// Only accept these kinds of receipts: composite, succinct or groth16
+    #[clap(long, value_delimiter = ',')]
+    pub accept_kinds: Vec<String>,
+    /// Release of risc0 which made the receipt, detected from the receipt by default
+    #[clap(long)]
+    pub risc0_version: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct MidenArgs {
+    pub program_hash: String,
+    pub proof_path: String,
+    pub stack_inputs_path: String,
+    pub stack_outputs_path: String,
+}
+
+#[derive(Args, Debug)]
+pub struct AutoArgs {
+    pub program_id: String,
+    pub proof_path: String,
+    /// Stack inputs file, for Miden proofs
+    #[clap(long, requires = "stack_outputs")]
+    pub stack_inputs: Option<String>,
+    /// Stack outputs file, for Miden proofs
+    #[clap(long, requires = "stack_inputs")]
+    pub stack_outputs: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct BatchArgs {
+    pub manifest_path: String,
+    /// Number of proofs verified in parallel, defaults to the number of cores
+    #[clap(long)]
+    pub jobs: Option<usize>,
+}
+
+#[derive(Args, Debug)]
+pub struct ServeArgs {
+    /// Listen on this unix socket instead of stdin
+    #[clap(long)]
+    pub socket: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct VerifierArgs {
+    #[clap(subcommand)]
+    pub entity: VerifierEntity,
+}
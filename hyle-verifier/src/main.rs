@@ -0,0 +1,118 @@
+use base64::prelude::*;
+use clap::Parser;
+use hyle_verifier_core::VerifyError;
+
+use crate::backend::Backend;
+use crate::commands::{VerifierArgs, VerifierEntity};
+
+mod backend;
+mod batch;
+mod commands;
+mod server;
+
+fn main() {
+    let args = VerifierArgs::parse();
+    // Fake receipts are never accepted here: the risc0 backend verifies with allow_dev_mode off.
+    // risc0's own verification accepts them when RISC0_DEV_MODE is set, so an inherited value
+    // is cleared in case a receipt reaches it.
+    std::env::remove_var("RISC0_DEV_MODE");
+
+    let res = match args.entity {
+        VerifierEntity::Risc0(args) => {
+            let mut extra_inputs = serde_json::Map::new();
+            if let Some(schema) = &args.outputs_schema {
+                extra_inputs.insert("outputs_schema".to_string(), schema.clone().into());
+            }
+            if !args.assumptions.is_empty() {
+                let receipts: Vec<String> =
+                    args.assumptions.iter().map(|path| BASE64_STANDARD.encode(read_file(path))).collect();
+                extra_inputs.insert("assumptions".to_string(), receipts.into());
+            }
+            if !args.accept_kinds.is_empty() {
+                extra_inputs.insert("accept_kinds".to_string(), args.accept_kinds.clone().into());
+            }
+            if let Some(version) = &args.risc0_version {
+                extra_inputs.insert("risc0_version".to_string(), version.clone().into());
+            }
+            let extra_inputs = serde_json::Value::Object(extra_inputs);
+            backend::verify(Some(Backend::Risc0), &args.program_id, &read_file(&args.proof_path), &extra_inputs)
+        }
+        #[cfg(feature = "sp1")]
+        VerifierEntity::Sp1(args) => {
+            let mut extra_inputs = serde_json::Map::new();
+            if let Some(vk_hash) = &args.vk_hash {
+                extra_inputs.insert("vk_hash".to_string(), vk_hash.clone().into());
+            }
+            if let Some(schema) = &args.outputs_schema {
+                extra_inputs.insert("outputs_schema".to_string(), schema.clone().into());
+            }
+            if !args.accept_modes.is_empty() {
+                extra_inputs.insert("accept_modes".to_string(), args.accept_modes.clone().into());
+            }
+            let extra_inputs = serde_json::Value::Object(extra_inputs);
+            backend::verify(Some(Backend::Sp1), &args.program_id, &read_file(&args.proof_path), &extra_inputs)
+        }
+        VerifierEntity::Cairo(args) => {
+            let extra_inputs = match &args.min_security {
+                Some(min_security) => serde_json::json!({ "min_security": min_security.to_string() }),
+                None => serde_json::Value::Null,
+            };
+            backend::verify(Some(Backend::Cairo), &args.program_id, &read_file(&args.proof_path), &extra_inputs)
+        }
+        VerifierEntity::Miden(args) => {
+            let stack = read_stack(&args.stack_inputs_path, &args.stack_outputs_path);
+            backend::verify(Some(Backend::Miden), &args.program_hash, &read_file(&args.proof_path), &stack)
+        }
+        VerifierEntity::Auto(args) => {
+            let proof = read_file(&args.proof_path);
+            let stack = match (&args.stack_inputs, &args.stack_outputs) {
+                (Some(inputs), Some(outputs)) => read_stack(inputs, outputs),
+                _ => serde_json::Value::Null,
+            };
+            backend::verify(None, &args.program_id, &proof, &stack)
+        }
+        VerifierEntity::Batch(args) => {
+            let report = batch::run(&args.manifest_path, args.jobs).unwrap_or_else(|err| err.exit());
+            println!("{}", serde_json::to_string(&report).expect("Failed to serialize report"));
+            std::process::exit(report.exit_code());
+        }
+        VerifierEntity::Serve(args) => {
+            let res = match args.socket {
+                #[cfg(unix)]
+                Some(socket) => server::serve_socket(&socket),
+                #[cfg(not(unix))]
+                Some(_) => Err(std::io::Error::other("unix sockets are not supported on this platform")),
+                None => server::serve_stdio(),
+            };
+            if let Err(err) = res {
+                VerifyError::Io(err.to_string()).exit();
+            }
+            return;
+        }
+    };
+
+    match res {
+        Ok(output) => {
+            // Outputs to stdout for the caller to read.
+            println!("{}", serde_json::to_string(&output).expect("Failed to serialize output"));
+        }
+        Err(err) => err.exit(),
+    }
+}
+
+fn read_file(path: &str) -> Vec<u8> {
+    hyle_verifier_core::read_file(path).unwrap_or_else(|err| err.exit())
+}
+
+/// Reads the Miden stack files into the extra inputs expected by the Miden verifier.
+fn read_stack(inputs_path: &str, outputs_path: &str) -> serde_json::Value {
+    let read_json = |path: &str| -> serde_json::Value {
+        serde_json::from_slice(&read_file(path)).unwrap_or_else(|err| {
+            VerifyError::MalformedProof(format!("Failed to parse {}: {}", path, err)).exit()
+        })
+    };
+    serde_json::json!({
+        "stack_inputs": read_json(inputs_path),
+        "stack_outputs": read_json(outputs_path),
+    })
+}
@@ -0,0 +1,202 @@
+use std::io::{self, BufRead, BufReader, Write};
+
+use base64::prelude::*;
+use hyle_contract::HyleOutput;
+use hyle_verifier_core::{read_file, VerifyError};
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{self, Backend, Outcome};
+
+/// One verification request, sent as a single JSON line.
+#[derive(Deserialize, Debug)]
+pub struct Request {
+    /// Echoed back in the response, to match responses with requests.
+    #[serde(default)]
+    pub id: serde_json::Value,
+    /// Proof system of the proof, detected from the proof when omitted.
+    pub backend: Option<Backend>,
+    pub program_id: String,
+    /// Path of the proof file, when `proof` is not given.
+    pub proof_path: Option<String>,
+    /// Base64 encoded proof.
+    pub proof: Option<String>,
+    /// Stack inputs and outputs for Miden proofs, `outputs_schema` for RISC Zero and SP1 proofs.
+    #[serde(default)]
+    pub extra_inputs: serde_json::Value,
+}
+
+/// Response to a request, sent as a single JSON line.
+#[derive(Serialize, Debug)]
+pub struct Response {
+    pub id: serde_json::Value,
+    #[serde(flatten)]
+    pub result: Outcome,
+}
+
+/// Answers requests read on stdin until it is closed.
+pub fn serve_stdio() -> io::Result<()> {
+    handle(io::stdin().lock(), io::stdout().lock())
+}
+
+/// Answers requests of each connection to the socket, one thread per connection.
+#[cfg(unix)]
+pub fn serve_socket(path: &str) -> io::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::UnixListener;
+
+    // The socket of a previous server is left behind when it is killed, and binding fails on it
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(path)?,
+        Ok(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} exists and is not a socket", path),
+            ))
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err),
+    }
+    let listener = UnixListener::bind(path)?;
+    for stream in listener.incoming() {
+        let stream = stream?;
+        std::thread::spawn(move || {
+            let reader = match stream.try_clone() {
+                Ok(reader) => BufReader::new(reader),
+                Err(err) => return eprintln!("Failed to read from connection: {}", err),
+            };
+            if let Err(err) = handle(reader, stream) {
+                eprintln!("Connection closed: {}", err);
+            }
+        });
+    }
+    Ok(())
+}
+
+fn handle<R: BufRead, W: Write>(reader: R, writer: W) -> io::Result<()> {
+    handle_with(reader, writer, process)
+}
+
+/// Answers each line read with the response of `process`, or with an error when the line is
+/// not a request. A panic of `process` is turned into an error, so that the server and the
+/// other requests of the connection carry on.
+fn handle_with<R, W, F>(mut reader: R, mut writer: W, process: F) -> io::Result<()>
+where
+    R: BufRead,
+    W: Write,
+    F: Fn(&Request) -> Result<HyleOutput<serde_json::Value>, VerifyError>,
+{
+    let mut line = vec![];
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            return Ok(());
+        }
+        // A line which is not UTF-8 is answered like any other invalid request
+        let request = std::str::from_utf8(&line)
+            .map_err(|err| err.to_string())
+            .and_then(|line| match line.trim() {
+                "" => Ok(None),
+                line => serde_json::from_str::<Request>(line).map(Some).map_err(|err| err.to_string()),
+            });
+        let response = match request {
+            Ok(None) => continue,
+            Ok(Some(request)) => Response {
+                id: request.id.clone(),
+                result: backend::catch_panic(|| process(&request)).into(),
+            },
+            Err(err) => Response {
+                id: serde_json::Value::Null,
+                result: Outcome::Error(VerifyError::MalformedProof(format!("Invalid request: {}", err))),
+            },
+        };
+        serde_json::to_writer(&mut writer, &response)?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+    }
+}
+
+fn process(request: &Request) -> Result<HyleOutput<serde_json::Value>, VerifyError> {
+    let proof = match (&request.proof, &request.proof_path) {
+        (Some(proof), _) => BASE64_STANDARD
+            .decode(proof)
+            .map_err(|err| VerifyError::MalformedProof(format!("Invalid base64 proof: {}", err)))?,
+        (None, Some(path)) => read_file(path)?,
+        (None, None) => {
+            return Err(VerifyError::MalformedProof(
+                "One of proof or proof_path is required".to_string(),
+            ))
+        }
+    };
+    backend::verify(request.backend, &request.program_id, &proof, &request.extra_inputs)
+}
+
+#[cfg(test)]
+mod test {
+    use hyle_contract::HyleOutput;
+    use hyle_verifier_core::VerifyError;
+
+    use super::{handle, handle_with, Request};
+
+    fn output(request: &Request) -> Result<HyleOutput<serde_json::Value>, VerifyError> {
+        Ok(HyleOutput {
+            version: 1,
+            initial_state: vec![],
+            next_state: vec![],
+            origin: String::new(),
+            caller: String::new(),
+            block_number: 0,
+            block_time: 0,
+            tx_hash: vec![],
+            program_outputs: request.program_id.clone().into(),
+        })
+    }
+
+    fn responses<F>(input: &[u8], process: F) -> Vec<serde_json::Value>
+    where
+        F: Fn(&Request) -> Result<HyleOutput<serde_json::Value>, VerifyError>,
+    {
+        let mut writer = vec![];
+        handle_with(input, &mut writer, process).unwrap();
+        let writer = String::from_utf8(writer).unwrap();
+        writer.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_handle() {
+        let input = b"{\"id\": 1, \"program_id\": \"0x1\", \"proof\": \"\"}\n\n{\"id\": 2\n\xff\xfe\n{\"id\": 3, \"program_id\": \"0x3\", \"proof\": \"\"}";
+        let responses = responses(input, output);
+        assert_eq!(responses.len(), 4, "{:?}", responses);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["output"]["program_outputs"], "0x1");
+        // Invalid JSON, then a line which is not UTF-8, do not stop the requests after them
+        for response in &responses[1..3] {
+            assert_eq!(response["id"], serde_json::Value::Null);
+            assert_eq!(response["error"], "malformed_proof");
+        }
+        assert_eq!(responses[3]["id"], 3);
+        assert_eq!(responses[3]["output"]["program_outputs"], "0x3");
+    }
+
+    #[test]
+    fn test_handle_panic() {
+        let input = b"{\"id\": 1, \"program_id\": \"panic\"}\n{\"id\": 2, \"program_id\": \"0x2\"}\n";
+        let responses = responses(input, |request| match request.program_id.as_str() {
+            "panic" => panic!("backend failure"),
+            _ => output(request),
+        });
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[0]["error"], "internal");
+        assert!(responses[0]["message"].as_str().unwrap().contains("backend failure"));
+        assert_eq!(responses[1]["output"]["program_outputs"], "0x2");
+    }
+
+    #[test]
+    fn test_handle_backend_error() {
+        let input = b"{\"id\": \"a\", \"backend\": \"risc0\", \"program_id\": \"0x1\", \"proof\": \"not base64\"}\n";
+        let mut writer = vec![];
+        handle(&input[..], &mut writer).unwrap();
+        let response: serde_json::Value = serde_json::from_slice(&writer).unwrap();
+        assert_eq!(response["id"], "a");
+        assert_eq!(response["error"], "malformed_proof");
+    }
+}
@@ -358,13 +358,20 @@ impl OutputFile {
 
     /// Converts outputs vectors for stack and overflow addresses to [StackOutputs].
     pub fn stack_outputs(&self) -> Result<StackOutputs, String> {
-        let stack = self.stack.iter().map(|v| v.parse::<u64>().unwrap()).collect::<Vec<u64>>();
+        let stack = self
+            .stack
+            .iter()
+            .map(|v| v.parse::<u64>().map_err(|e| format!("failed to parse stack value '{v}': {e}")))
+            .collect::<Result<Vec<u64>, String>>()?;
 
         let overflow_addrs = self
             .overflow_addrs
             .iter()
-            .map(|v| v.parse::<u64>().unwrap())
-            .collect::<Vec<u64>>();
+            .map(|v| {
+                v.parse::<u64>()
+                    .map_err(|e| format!("failed to parse overflow address '{v}': {e}"))
+            })
+            .collect::<Result<Vec<u64>, String>>()?;
 
         StackOutputs::try_from_ints(stack, overflow_addrs)
             .map_err(|e| format!("Construct stack outputs failed {e}"))
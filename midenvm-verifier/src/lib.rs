@@ -0,0 +1,77 @@
+pub mod helpers;
+pub mod output;
+
+use miden_verifier::{verify, ProgramInfo, Kernel};
+use miden_vm::{Digest, ExecutionProof};
+use serde_derive::Deserialize;
+use hyle_contract::HyleOutput;
+use hyle_verifier_core::{to_json_output, Verifier, VerifyError};
+use crate::helpers::ProgramHash;
+use crate::helpers::InputFile;
+use crate::helpers::OutputFile;
+
+/// Verifies a proof against its public stack inputs and outputs, and maps them to an HyleOutput.
+pub fn verify_stack(
+    program_hash: Digest,
+    input_data: &InputFile,
+    outputs_data: &OutputFile,
+    proof: ExecutionProof,
+) -> Result<HyleOutput<Vec<u64>>, VerifyError> {
+    // Fetch the stack inputs and outputs from the arguments
+    let stack_inputs = input_data.parse_stack_inputs().map_err(VerifyError::MalformedProof)?;
+    let stack_outputs = outputs_data.stack_outputs().map_err(VerifyError::MalformedProof)?;
+
+    // This is copied from core midenvm verifier.
+    // TODO accept kernel as CLI argument -- this is not done in core midenVM
+    let kernel = Kernel::default();
+    let program_info = ProgramInfo::new(program_hash, kernel);
+
+    // verify proof
+    verify(program_info, stack_inputs, stack_outputs, proof)
+        .map_err(|err| VerifyError::VerificationFailed(format!("Program failed verification! - {}", err)))?;
+
+    // The stacks are public inputs of the proof, so the output can be built from them.
+    let inputs = parse_ints(&input_data.operand_stack).map_err(VerifyError::MalformedProof)?;
+    let outputs = parse_ints(&outputs_data.stack).map_err(VerifyError::MalformedProof)?;
+    output::to_hyle_output(&inputs, &outputs).map_err(VerifyError::OutputDecode)
+}
+
+fn parse_ints(values: &[String]) -> Result<Vec<u64>, String> {
+    values
+        .iter()
+        .map(|v| v.parse::<u64>().map_err(|e| format!("failed to parse stack value '{v}': {e}")))
+        .collect()
+}
+
+/// Public inputs of a Miden proof, in the same format as the inputs and outputs files.
+#[derive(Deserialize, Debug)]
+pub struct ExtraInputs {
+    pub stack_inputs: InputFile,
+    pub stack_outputs: OutputFile,
+}
+
+pub struct MidenVerifier;
+
+impl Verifier for MidenVerifier {
+    fn name(&self) -> &'static str {
+        "miden"
+    }
+
+    /// `program_id` is the hex encoded program hash, `proof` a serialized [ExecutionProof]
+    /// and `extra_inputs` the [ExtraInputs] of the proof.
+    fn verify(
+        &self,
+        program_id: &str,
+        proof: &[u8],
+        extra_inputs: &serde_json::Value,
+    ) -> Result<HyleOutput<serde_json::Value>, VerifyError> {
+        let program_hash = ProgramHash::read(&program_id.to_string()).map_err(VerifyError::WrongProgramId)?;
+        let extra_inputs: ExtraInputs = serde_json::from_value(extra_inputs.clone())
+            .map_err(|err| VerifyError::MalformedProof(format!("Invalid stack inputs or outputs - {}", err)))?;
+        let proof = ExecutionProof::from_bytes(proof)
+            .map_err(|err| VerifyError::MalformedProof(format!("Failed to decode proof data - {}", err)))?;
+
+        let output = verify_stack(program_hash, &extra_inputs.stack_inputs, &extra_inputs.stack_outputs, proof)?;
+        to_json_output(output)
+    }
+}
@@ -1,60 +1,54 @@
-// TODO: some of the helpers.rs is currently only used in tests, ignore the warnings for now.
-#![allow(
-    dead_code,
-    unused_imports
-)]
-
-mod helpers;
-
-use miden_verifier::{verify, ProgramInfo, Kernel};
-use miden_vm::utils::Serializable;
 use std::env;
 use std::path::PathBuf;
 use std::path::Path;
-use crate::helpers::ProgramHash;
-use crate::helpers::InputFile;
-use crate::helpers::OutputFile;
-use crate::helpers::ProofFile;
+use midenvm_verifier::helpers::ProgramHash;
+use midenvm_verifier::helpers::InputFile;
+use midenvm_verifier::helpers::OutputFile;
+use midenvm_verifier::helpers::ProofFile;
+use midenvm_verifier::verify_stack;
 use hyle_contract::HyleOutput;
+use hyle_verifier_core::{VerifyError, EXIT_USAGE};
 
 
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() != 5 {
         eprintln!("Usage: {} <program_hash> <proof_path> <stack_inputs> <stack_outputs>", args[0]);
-        std::process::exit(1);
+        std::process::exit(EXIT_USAGE);
     }
 
+    match run(&args[1], &args[2], &args[3], &args[4]) {
+        Ok(output) => {
+            // Outputs to stdout for the caller to read.
+            println!("{}", serde_json::to_string(&output).expect("Failed to serialize output"));
+        }
+        Err(err) => err.exit(),
+    }
+}
+
+fn run(
+    program_hash: &String,
+    proof_path: &str,
+    inputs_path: &str,
+    outputs_path: &str,
+) -> Result<HyleOutput<Vec<u64>>, VerifyError> {
     // Read program hash from the input.
-    let program_hash = ProgramHash::read(&args[1]).unwrap();
+    let program_hash = ProgramHash::read(program_hash).map_err(VerifyError::WrongProgramId)?;
     // Make required types for reading files.
-    let mut input_path = PathBuf::new();
-    input_path.push(args[3].clone()); 
-    let mut output_path = PathBuf::new();
-    output_path.push(args[4].clone());
-    let proof_path = Path::new(&args[2]);
+    let input_path = PathBuf::from(inputs_path);
+    let output_path = PathBuf::from(outputs_path);
+    let proof_path = Path::new(proof_path);
 
     // Load files.
-    let input_data = InputFile::read(&Some(input_path), proof_path).unwrap();
-    let outputs_data = OutputFile::read(&Some(output_path), proof_path).unwrap();
-
-    // Fetch the stack inputs and outputs from the arguments
-    let stack_inputs = input_data.parse_stack_inputs().unwrap();
-    let stack_outputs = outputs_data.stack_outputs().unwrap();
+    // The helpers report read and parse failures alike, check the files can be read first.
+    for path in [&input_path, &output_path, &proof_path.to_path_buf()] {
+        std::fs::metadata(path).map_err(|err| VerifyError::Io(format!("Failed to read {}: {}", path.display(), err)))?;
+    }
+    let input_data = InputFile::read(&Some(input_path), proof_path).map_err(VerifyError::MalformedProof)?;
+    let outputs_data = OutputFile::read(&Some(output_path), proof_path).map_err(VerifyError::MalformedProof)?;
 
     // Load the proof from file.
-    let proof = ProofFile::read(&Some(proof_path.to_path_buf()), proof_path).unwrap();
-
-    // This is copied from core midenvm verifier.
-    // TODO accept kernel as CLI argument -- this is not done in core midenVM
-    let kernel = Kernel::default();
-    let program_info = ProgramInfo::new(program_hash, kernel);
-
-    // verify proof
-    let result = verify(program_info, stack_inputs, stack_outputs, proof)
-        .map_err(|err| format!("Program failed verification! - {}", err));
+    let proof = ProofFile::read(&Some(proof_path.to_path_buf()), proof_path).map_err(VerifyError::MalformedProof)?;
 
-    eprintln!("result: {:?}", result);
-    // TODO: what to put for other fields?
-    // let output: HyleOutput<()> = HyleOutput { initial_state: stack_inputs.to_bytes(), next_state: stack_outputs.to_bytes(), origin: (), caller: (), block_number: (), block_time: (), tx_hash: (), program_outputs: () };
+    verify_stack(program_hash, &input_data, &outputs_data, proof)
 }
@@ -0,0 +1,86 @@
+use hyle_contract::HyleOutput;
+
+// HYLE OUTPUT
+// ================================================================================================
+
+/// Version of the mapping below, reported in the `version` field of the [HyleOutput].
+pub const HYLE_OUTPUT_VERSION: u32 = 1;
+
+/// Number of elements of the operand stack a program can return.
+const STACK_OUTPUT_LEN: usize = 16;
+
+/// Builds the [HyleOutput] of a Miden program from its public stack inputs and outputs.
+///
+/// Both are given top of the stack first, as they appear in the inputs and outputs files.
+/// Missing elements are read as zeros, like the VM does for an uninitialized stack.
+///
+/// Stack inputs:
+/// - `[0..4]`: initial_state
+///
+/// Stack outputs:
+/// - `[0..4]`: next_state
+/// - `[4..8]`: tx_hash
+/// - `[8]`: block_number
+/// - `[9]`: block_time
+/// - `[10]`: origin
+/// - `[11]`: caller
+/// - `[12..16]`: program_outputs
+///
+/// Words are encoded as the big-endian bytes of their 4 elements, origin and caller as the
+/// decimal representation of their element.
+pub fn to_hyle_output(
+    stack_inputs: &[u64],
+    stack_outputs: &[u64],
+) -> Result<HyleOutput<Vec<u64>>, String> {
+    if stack_outputs.len() > STACK_OUTPUT_LEN {
+        return Err(format!(
+            "Expected at most {} stack outputs, got {}",
+            STACK_OUTPUT_LEN,
+            stack_outputs.len()
+        ));
+    }
+    let element = |values: &[u64], i: usize| values.get(i).copied().unwrap_or(0);
+    let word = |values: &[u64], start: usize| {
+        (start..start + 4)
+            .flat_map(|i| element(values, i).to_be_bytes())
+            .collect::<Vec<u8>>()
+    };
+
+    Ok(HyleOutput {
+        version: HYLE_OUTPUT_VERSION,
+        initial_state: word(stack_inputs, 0),
+        next_state: word(stack_outputs, 0),
+        origin: element(stack_outputs, 10).to_string(),
+        caller: element(stack_outputs, 11).to_string(),
+        block_number: element(stack_outputs, 8),
+        block_time: element(stack_outputs, 9),
+        tx_hash: word(stack_outputs, 4),
+        program_outputs: (12..STACK_OUTPUT_LEN).map(|i| element(stack_outputs, i)).collect(),
+    })
+}
+
+// TESTS
+// ================================================================================================
+#[cfg(test)]
+mod test {
+    use super::to_hyle_output;
+
+    #[test]
+    fn test_hyle_output_mapping() {
+        let outputs = [5, 6, 0, 0, 1, 2, 3, 4, 42, 1700000000, 7, 8, 9, 10, 11, 12];
+        let output = to_hyle_output(&[1], &outputs).unwrap();
+
+        assert_eq!(output.version, 1);
+        assert_eq!(output.initial_state, [[0, 0, 0, 0, 0, 0, 0, 1], [0; 8], [0; 8], [0; 8]].concat());
+        assert_eq!(output.next_state, [[0, 0, 0, 0, 0, 0, 0, 5], [0, 0, 0, 0, 0, 0, 0, 6], [0; 8], [0; 8]].concat());
+        assert_eq!(output.tx_hash.len(), 32);
+        assert_eq!(output.tx_hash[31], 4);
+        assert_eq!(output.block_number, 42);
+        assert_eq!(output.block_time, 1700000000);
+        assert_eq!(output.origin, "7");
+        assert_eq!(output.caller, "8");
+        assert_eq!(output.program_outputs, vec![9, 10, 11, 12]);
+
+        assert!(to_hyle_output(&[], &[0; 17]).is_err());
+    }
+}
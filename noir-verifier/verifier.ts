@@ -70,18 +70,48 @@ function deserializePublicInputs<T>(publicInputs: string[]): HyleOutput {
   };
 }
 
-const proof = JSON.parse(fs.readFileSync(values.proofPath, { encoding: 'utf8' }));
-const b64vKey = fs.readFileSync(values.vKeyPath, { encoding: 'utf8' });
+// Same errors and exit codes as the rust verifiers
+function exitWithError(error: string, code: number, message: string): never {
+  process.stderr.write(JSON.stringify({ error, message }) + "\n");
+  process.exit(code);
+}
+
+let proofContent: string, b64vKey: string;
+try {
+  proofContent = fs.readFileSync(values.proofPath, { encoding: 'utf8' });
+  b64vKey = fs.readFileSync(values.vKeyPath, { encoding: 'utf8' });
+} catch (e) {
+  exitWithError("io", 7, String(e));
+}
+let proof;
+try {
+  proof = JSON.parse(proofContent);
+} catch (e) {
+  exitWithError("malformed_proof", 3, String(e));
+}
 const vKey = Uint8Array.from(Buffer.from(b64vKey, 'base64'));
 
-const deserializedProofData: ProofData = {
-  proof: Uint8Array.from(proof.proof),
-  publicInputs: proof.publicInputs
-};
+let deserializedProofData: ProofData;
+try {
+  if (!Array.isArray(proof.proof) || !Array.isArray(proof.publicInputs)) {
+    throw new Error("expected proof and publicInputs arrays");
+  }
+  deserializedProofData = {
+    proof: Uint8Array.from(proof.proof),
+    publicInputs: proof.publicInputs
+  };
+} catch (e) {
+  exitWithError("malformed_proof", 3, String(e));
+}
 
-// Verifying
-const verifier = new Verifier();
-const isValid = await verifier.verifyProof(deserializedProofData, vKey);
+// Verifying, bb.js throws on proofs or keys it can't read
+let isValid: boolean;
+try {
+  const verifier = new Verifier();
+  isValid = await verifier.verifyProof(deserializedProofData, vKey);
+} catch (e) {
+  exitWithError("verification_failed", 5, String(e));
+}
 if (isValid){
   const hyleOutput = deserializePublicInputs(deserializedProofData.publicInputs);
 
@@ -95,5 +125,5 @@ if (isValid){
   process.exit(0);
 }
 else {
-  process.exit(1);
+  exitWithError("verification_failed", 5, "Noir proof verification failed");
 }
\ No newline at end of file
@@ -0,0 +1,329 @@
+use risc0_zkvm::sha::{Digest, Digestible};
+use risc0_zkvm::{
+    Assumptions, ExitCode, InnerReceipt, MaybePruned, Receipt, ReceiptClaim, VerifierContext,
+};
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::kind::ReceiptKind;
+
+thread_local! {
+    // Building the context sets up the hash suites of every circuit, which is not worth
+    // repeating for each receipt. It is not Sync, so it is built once per thread.
+    static VERIFIER_CONTEXT: VerifierContext = VerifierContext::default();
+}
+
+/// What [verify_claim] verified.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedClaim {
+    /// Digests of the claims the receipt assumed, resolved by embedded or given receipts.
+    pub assumptions: Vec<Digest>,
+    /// Whether the receipt, or one of its assumptions, is a fake receipt made in dev mode.
+    pub dev_mode: bool,
+}
+
+/// Verifies the receipt proves an execution of the image id which halted successfully and
+/// committed its journal.
+///
+/// Receipts without assumptions are verified as is. Assumptions of a receipt are resolved by
+/// the assumption receipts embedded in it, or by one of `assumption_receipts`, which are
+/// verified in turn.
+///
+/// Fake receipts are rejected unless `allow_dev_mode` is set, whatever `RISC0_DEV_MODE` is. When
+/// it is set, only their claim is checked, as they have no seal.
+pub fn verify_claim(
+    image_id: Digest,
+    receipt: &Receipt,
+    assumption_receipts: &[Receipt],
+    allow_dev_mode: bool,
+) -> Result<VerifiedClaim, Error> {
+    let fake = is_fake(&receipt.inner);
+    check_dev_mode(fake, allow_dev_mode)?;
+
+    // Checking the claim first gives a clear error for guests which did not halt successfully
+    let claim = receipt.get_claim().map_err(Error::Verification)?;
+    check_claim(&claim, image_id, &receipt.journal.bytes)?;
+    let assumptions = assumption_digests(&claim)?;
+    // The claim of a composite receipt leaves out the assumptions its embedded receipts
+    // resolve, they are reported all the same
+    let embedded = embedded_assumptions(receipt)?;
+    if assumptions.is_empty() {
+        if !fake {
+            VERIFIER_CONTEXT
+                .with(|ctx| receipt.verify_with_context(ctx, image_id))
+                .map_err(Error::Verification)?;
+        }
+        return Ok(VerifiedClaim { assumptions: embedded, dev_mode: fake });
+    }
+
+    // Receipt::verify expects no assumptions, the claim was checked above
+    if !fake {
+        VERIFIER_CONTEXT
+            .with(|ctx| receipt.verify_integrity_with_context(ctx))
+            .map_err(Error::Verification)?;
+    }
+    let (mut resolved, fake_assumptions) = resolved_assumptions(assumption_receipts, allow_dev_mode)?;
+    resolved.extend_from_slice(&embedded);
+    if let Some(missing) = assumptions.iter().find(|digest| !resolved.contains(digest)) {
+        return Err(Error::Claim(format!(
+            "unresolved assumption {}, pass its receipt to resolve it",
+            hex(missing)
+        )));
+    }
+    Ok(VerifiedClaim {
+        assumptions: merge_assumptions(assumptions, &embedded),
+        dev_mode: fake || fake_assumptions,
+    })
+}
+
+/// Whether the receipt, or an assumption receipt embedded in it, is fake.
+fn is_fake(receipt: &InnerReceipt) -> bool {
+    match receipt {
+        InnerReceipt::Fake { .. } => true,
+        InnerReceipt::Composite(composite) => composite.assumptions.iter().any(is_fake),
+        _ => false,
+    }
+}
+
+fn check_dev_mode(fake: bool, allow_dev_mode: bool) -> Result<(), Error> {
+    if fake && !allow_dev_mode {
+        return Err(Error::ReceiptKind(
+            "fake receipts are only accepted in dev mode".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks the claim is for the image id, that the guest halted with exit code 0, and that it
+/// committed the journal.
+pub fn check_claim(claim: &ReceiptClaim, image_id: Digest, journal: &[u8]) -> Result<(), Error> {
+    if claim.pre.digest() != image_id {
+        return Err(Error::WrongImageId(format!(
+            "the receipt is for image id {}",
+            hex(&claim.pre.digest())
+        )));
+    }
+    match claim.exit_code {
+        ExitCode::Halted(0) => {}
+        ExitCode::Halted(code) => {
+            return Err(Error::Claim(format!("the guest exited with code {}", code)))
+        }
+        ExitCode::Paused(code) => {
+            return Err(Error::Claim(format!(
+                "the guest paused with code {}, it did not finish",
+                code
+            )))
+        }
+        ExitCode::SystemSplit | ExitCode::SessionLimit => {
+            return Err(Error::Claim(format!(
+                "the execution stopped with {:?}, it did not finish",
+                claim.exit_code
+            )))
+        }
+    }
+    let journal_digest = match &claim.output {
+        MaybePruned::Value(Some(output)) => output.journal.digest(),
+        _ => return Err(Error::Claim("the claim has no output".to_string())),
+    };
+    if journal_digest != journal.digest() {
+        return Err(Error::Claim(
+            "the journal does not match the claim".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Details of the claim of a receipt, for diagnostics.
+#[derive(Serialize, Debug)]
+pub struct ClaimInfo {
+    pub receipt_kind: ReceiptKind,
+    pub claim_digest: String,
+    /// Exit code of the guest, `Halted(0)` when it finished successfully.
+    pub exit_code: String,
+    /// Digest of the state before the execution, which is the image id.
+    pub pre_state_digest: String,
+    pub post_state_digest: String,
+    pub input_digest: String,
+    pub journal_length: usize,
+    pub journal_digest: String,
+    pub assumptions: Vec<String>,
+}
+
+/// Reads the claim of a receipt, without verifying it.
+pub fn inspect_claim(receipt: &Receipt) -> Result<ClaimInfo, Error> {
+    let claim = receipt.get_claim().map_err(Error::Verification)?;
+    Ok(ClaimInfo {
+        receipt_kind: ReceiptKind::of(receipt),
+        claim_digest: hex(&claim.digest()),
+        exit_code: format!("{:?}", claim.exit_code),
+        pre_state_digest: hex(&claim.pre.digest()),
+        post_state_digest: hex(&claim.post.digest()),
+        input_digest: hex(&claim.input),
+        journal_length: receipt.journal.bytes.len(),
+        journal_digest: hex(&receipt.journal.bytes.digest()),
+        assumptions: merge_assumptions(assumption_digests(&claim)?, &embedded_assumptions(receipt)?)
+            .iter()
+            .map(hex)
+            .collect(),
+    })
+}
+
+/// Digests of the claims the receipt claim assumes.
+pub fn assumption_digests(claim: &ReceiptClaim) -> Result<Vec<Digest>, Error> {
+    let assumptions = match &claim.output {
+        MaybePruned::Value(Some(output)) => &output.assumptions,
+        MaybePruned::Value(None) => return Ok(vec![]),
+        MaybePruned::Pruned(_) => return Err(Error::Claim("the output is pruned".to_string())),
+    };
+    match assumptions {
+        MaybePruned::Value(assumptions) => Ok(assumptions.0.iter().map(|claim| claim.digest()).collect()),
+        MaybePruned::Pruned(digest) if *digest == Assumptions(vec![]).digest() => Ok(vec![]),
+        MaybePruned::Pruned(_) => Err(Error::Claim("the assumptions are pruned".to_string())),
+    }
+}
+
+/// Claim digests of the assumptions embedded in a composite receipt, which its verification
+/// checked.
+fn embedded_assumptions(receipt: &Receipt) -> Result<Vec<Digest>, Error> {
+    match &receipt.inner {
+        InnerReceipt::Composite(composite) => composite
+            .assumptions
+            .iter()
+            .map(|assumption| Ok(assumption.get_claim().map_err(Error::Verification)?.digest()))
+            .collect(),
+        _ => Ok(vec![]),
+    }
+}
+
+/// Adds the embedded assumptions missing from the assumptions of the claim.
+fn merge_assumptions(mut assumptions: Vec<Digest>, embedded: &[Digest]) -> Vec<Digest> {
+    for digest in embedded {
+        if !assumptions.contains(digest) {
+            assumptions.push(*digest);
+        }
+    }
+    assumptions
+}
+
+/// Claim digests of the assumption receipts given, after verifying them. Also returns whether
+/// any of them is fake.
+fn resolved_assumptions(
+    assumption_receipts: &[Receipt],
+    allow_dev_mode: bool,
+) -> Result<(Vec<Digest>, bool), Error> {
+    let mut resolved = vec![];
+    let mut any_fake = false;
+    for assumption in assumption_receipts {
+        let fake = is_fake(&assumption.inner);
+        check_dev_mode(fake, allow_dev_mode)?;
+        any_fake |= fake;
+
+        let claim = assumption.get_claim().map_err(Error::Verification)?;
+        if !fake {
+            VERIFIER_CONTEXT
+                .with(|ctx| assumption.verify_integrity_with_context(ctx))
+                .map_err(Error::Verification)?;
+        }
+        if !assumption_digests(&claim)?.is_empty() {
+            return Err(Error::Claim(format!(
+                "assumption {} has unresolved assumptions itself",
+                hex(&claim.digest())
+            )));
+        }
+        resolved.push(claim.digest());
+    }
+    Ok((resolved, any_fake))
+}
+
+/// Lowercase hex of a digest.
+pub fn hex(digest: &Digest) -> String {
+    digest.as_bytes().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use risc0_zkvm::sha::{Digest, Digestible};
+    use risc0_zkvm::{
+        Assumptions, ExitCode, InnerReceipt, MaybePruned, Output, Receipt, ReceiptClaim,
+    };
+
+    use hyle_verifier_core::VerifyError;
+
+    use super::{check_claim, verify_claim};
+    use crate::error::Error;
+
+    fn claim(image_id: Digest, exit_code: ExitCode, journal: &[u8]) -> ReceiptClaim {
+        ReceiptClaim {
+            pre: MaybePruned::Pruned(image_id),
+            post: MaybePruned::Pruned(Digest::ZERO),
+            exit_code,
+            input: Digest::ZERO,
+            output: MaybePruned::Value(Some(Output {
+                journal: MaybePruned::Value(journal.to_vec()),
+                assumptions: MaybePruned::Value(Assumptions(vec![])),
+            })),
+        }
+    }
+
+    #[test]
+    fn test_check_claim() {
+        let image_id = [1u8; 32].digest();
+        let journal = [1, 0, 0, 0];
+
+        assert!(check_claim(&claim(image_id, ExitCode::Halted(0), &journal), image_id, &journal).is_ok());
+        let exit_code = |result: Result<(), Error>| VerifyError::from(result.unwrap_err()).exit_code();
+        let wrong_image_id = check_claim(&claim(image_id, ExitCode::Halted(0), &journal), Digest::ZERO, &journal);
+        assert_eq!(exit_code(wrong_image_id), 4);
+        let wrong_journal = check_claim(&claim(image_id, ExitCode::Halted(0), &journal), image_id, &[2, 0, 0, 0]);
+        assert_eq!(exit_code(wrong_journal), 5);
+        for code in [
+            ExitCode::Halted(1),
+            ExitCode::Paused(0),
+            ExitCode::SystemSplit,
+            ExitCode::SessionLimit,
+        ] {
+            assert_eq!(exit_code(check_claim(&claim(image_id, code, &journal), image_id, &journal)), 5);
+        }
+    }
+
+    #[test]
+    fn test_assumptions() {
+        let image_id = [1u8; 32].digest();
+        let journal = vec![1, 0, 0, 0];
+        let assumed = claim([2u8; 32].digest(), ExitCode::Halted(0), &[2, 0, 0, 0]);
+        let mut composed = claim(image_id, ExitCode::Halted(0), &journal);
+        composed.output = MaybePruned::Value(Some(Output {
+            journal: MaybePruned::Value(journal.clone()),
+            assumptions: MaybePruned::Value(Assumptions(vec![MaybePruned::Value(assumed.clone())])),
+        }));
+        let receipt = Receipt::new(InnerReceipt::Fake { claim: composed }, journal.clone());
+
+        let err = verify_claim(image_id, &receipt, &[], true).unwrap_err();
+        assert!(matches!(err, Error::Claim(_)), "{:?}", err);
+        // A receipt of another claim does not resolve it
+        let other = Receipt::new(
+            InnerReceipt::Fake { claim: claim(image_id, ExitCode::Halted(0), &journal) },
+            journal,
+        );
+        assert!(verify_claim(image_id, &receipt, &[other], true).is_err());
+
+        let assumption = Receipt::new(InnerReceipt::Fake { claim: assumed.clone() }, vec![2, 0, 0, 0]);
+        let verified = verify_claim(image_id, &receipt, &[assumption], true).unwrap();
+        assert_eq!(verified.assumptions, vec![assumed.digest()]);
+        assert!(verified.dev_mode);
+    }
+
+    #[test]
+    fn test_fake_receipts() {
+        let image_id = [1u8; 32].digest();
+        let journal = vec![1, 0, 0, 0];
+        let fake = Receipt::new(
+            InnerReceipt::Fake { claim: claim(image_id, ExitCode::Halted(0), &journal) },
+            journal,
+        );
+
+        assert!(verify_claim(image_id, &fake, &[], false).is_err());
+        assert!(verify_claim(image_id, &fake, &[], true).unwrap().dev_mode);
+        assert!(verify_claim(Digest::ZERO, &fake, &[], true).is_err());
+    }
+}
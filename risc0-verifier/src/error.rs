@@ -0,0 +1,63 @@
+use std::fmt;
+
+use hyle_verifier_core::VerifyError;
+
+/// Errors of the RISC Zero verifier.
+#[derive(Debug)]
+pub enum Error {
+    /// The image id could not be parsed.
+    InvalidImageId(String),
+    /// The image id of a guest ELF could not be computed.
+    InvalidElf(String),
+    /// The receipt could not be deserialized.
+    InvalidReceipt(String),
+    /// The kind of receipt is not accepted.
+    ReceiptKind(String),
+    /// The receipt proves an execution of another image id.
+    WrongImageId(String),
+    /// The receipt does not verify for this image id.
+    Verification(risc0_zkvm::VerificationError),
+    /// The risc0 1 receipt does not verify for this image id.
+    #[cfg(feature = "risc0-1")]
+    VerificationV1(String),
+    /// The claim of the receipt is not the one expected, e.g. its assumptions are unresolved.
+    Claim(String),
+    /// The journal is not an HyleOutput.
+    JournalDecode(risc0_zkvm::serde::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidImageId(msg) => write!(f, "Invalid image id: {}", msg),
+            Error::InvalidElf(msg) => write!(f, "Invalid guest ELF: {}", msg),
+            Error::InvalidReceipt(msg) => write!(f, "Invalid receipt: {}", msg),
+            Error::ReceiptKind(msg) => write!(f, "Receipt kind rejected: {}", msg),
+            Error::WrongImageId(msg) => write!(f, "Wrong image id: {}", msg),
+            Error::Verification(err) => write!(f, "Receipt verification failed: {}", err),
+            #[cfg(feature = "risc0-1")]
+            Error::VerificationV1(msg) => write!(f, "Receipt verification failed: {}", msg),
+            Error::Claim(msg) => write!(f, "Unexpected receipt claim: {}", msg),
+            Error::JournalDecode(err) => write!(f, "Failed to decode receipt journal: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for VerifyError {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::InvalidImageId(_) | Error::WrongImageId(_) => {
+                VerifyError::WrongProgramId(err.to_string())
+            }
+            Error::InvalidElf(_) | Error::InvalidReceipt(_) => VerifyError::MalformedProof(err.to_string()),
+            Error::ReceiptKind(_) | Error::Verification(_) | Error::Claim(_) => {
+                VerifyError::VerificationFailed(err.to_string())
+            }
+            #[cfg(feature = "risc0-1")]
+            Error::VerificationV1(_) => VerifyError::VerificationFailed(err.to_string()),
+            Error::JournalDecode(_) => VerifyError::OutputDecode(err.to_string()),
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use base64::prelude::*;
+use serde::Serialize;
+
+const IMAGE_ID_LEN: usize = 32;
+
+/// Parses the 32 bytes of an image id given as either:
+/// - 64 hexadecimal digits, with or without a `0x` prefix,
+/// - the base64 encoding of the 32 bytes, padded or not,
+/// - the `[u32; 8]` words risc0 tooling prints for a `METHOD_ID`, e.g. `[1, 2, 3, 4, 5, 6, 7, 8]`.
+pub fn parse_image_id_bytes(image_id: &str) -> Result<[u8; IMAGE_ID_LEN], String> {
+    let image_id = image_id.trim();
+    if image_id.starts_with('[') {
+        return parse_words(image_id);
+    }
+    if let Some(hex) = image_id.strip_prefix("0x").or_else(|| image_id.strip_prefix("0X")) {
+        return parse_hex(hex);
+    }
+    // Hexadecimal digits at the wrong length are an error, not base64 that happens to decode
+    if image_id.bytes().all(|c| c.is_ascii_hexdigit()) && !image_id.is_empty() {
+        return parse_hex(image_id);
+    }
+    parse_base64(image_id)
+}
+
+fn parse_hex(hex: &str) -> Result<[u8; IMAGE_ID_LEN], String> {
+    if hex.len() != 2 * IMAGE_ID_LEN {
+        return Err(format!(
+            "expected {} hexadecimal digits, got {}",
+            2 * IMAGE_ID_LEN,
+            hex.len()
+        ));
+    }
+    let mut bytes = [0u8; IMAGE_ID_LEN];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        let digits = hex
+            .get(2 * i..2 * i + 2)
+            .ok_or_else(|| format!("{} is not hexadecimal", hex))?;
+        *byte = u8::from_str_radix(digits, 16)
+            .map_err(|_| format!("invalid hexadecimal byte '{}'", digits))?;
+    }
+    Ok(bytes)
+}
+
+fn parse_base64(b64: &str) -> Result<[u8; IMAGE_ID_LEN], String> {
+    let bytes = BASE64_STANDARD
+        .decode(b64)
+        .or_else(|_| BASE64_STANDARD_NO_PAD.decode(b64))
+        .map_err(|_| {
+            format!(
+                "{} is neither {} hexadecimal digits, base64, nor [u32; 8] words",
+                b64,
+                2 * IMAGE_ID_LEN
+            )
+        })?;
+    bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| format!("expected {} bytes, got {}", IMAGE_ID_LEN, bytes.len()))
+}
+
+/// An image id in each of the forms [parse_image_id_bytes] accepts.
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct ImageIdForms {
+    pub hex: String,
+    pub base64: String,
+    pub words: [u32; IMAGE_ID_LEN / 4],
+}
+
+impl ImageIdForms {
+    pub fn new(image_id: &[u8; IMAGE_ID_LEN]) -> Self {
+        let mut words = [0u32; IMAGE_ID_LEN / 4];
+        for (word, chunk) in words.iter_mut().zip(image_id.chunks_exact(4)) {
+            *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        ImageIdForms {
+            hex: image_id.iter().map(|byte| format!("{:02x}", byte)).collect(),
+            base64: BASE64_STANDARD.encode(image_id),
+            words,
+        }
+    }
+}
+
+/// The words are little-endian, as in `risc0_zkvm::sha::Digest`.
+fn parse_words(words: &str) -> Result<[u8; IMAGE_ID_LEN], String> {
+    let Some(words) = words.strip_prefix('[').and_then(|words| words.strip_suffix(']')) else {
+        return Err(format!("{} is missing a closing bracket", words));
+    };
+    let words = words
+        .split(',')
+        .map(|word| {
+            let word = word.trim();
+            word.parse::<u32>()
+                .map_err(|err| format!("invalid u32 word '{}': {}", word, err))
+        })
+        .collect::<Result<Vec<u32>, String>>()?;
+    if words.len() != IMAGE_ID_LEN / 4 {
+        return Err(format!("expected {} u32 words, got {}", IMAGE_ID_LEN / 4, words.len()));
+    }
+    let mut bytes = [0u8; IMAGE_ID_LEN];
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(words) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_image_id_bytes, ImageIdForms};
+
+    const HEX: &str = "d7c6e0c07f3f5f67a2a5d4f60f6f3a4c0e1d2c3b4a5968778695a4b3c2d1e0f1";
+
+    fn expected() -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        hex_to_bytes(HEX, &mut bytes);
+        bytes
+    }
+
+    fn hex_to_bytes(hex: &str, bytes: &mut [u8]) {
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_parse_hex() {
+        assert_eq!(parse_image_id_bytes(HEX).unwrap(), expected());
+        assert_eq!(parse_image_id_bytes(&format!("0x{}", HEX)).unwrap(), expected());
+        assert_eq!(parse_image_id_bytes(&HEX.to_uppercase()).unwrap(), expected());
+        assert_eq!(parse_image_id_bytes(&format!(" {}\n", HEX)).unwrap(), expected());
+    }
+
+    #[test]
+    fn test_parse_base64() {
+        use base64::prelude::*;
+
+        let b64 = BASE64_STANDARD.encode(expected());
+        assert_eq!(parse_image_id_bytes(&b64).unwrap(), expected());
+        assert_eq!(parse_image_id_bytes(b64.trim_end_matches('=')).unwrap(), expected());
+        assert!(parse_image_id_bytes(&BASE64_STANDARD.encode([1u8; 31])).is_err());
+    }
+
+    #[test]
+    fn test_parse_words() {
+        let words: Vec<String> = expected()
+            .chunks_exact(4)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()).to_string())
+            .collect();
+        let image_id = format!("[{}]", words.join(", "));
+        assert_eq!(parse_image_id_bytes(&image_id).unwrap(), expected());
+        assert_eq!(parse_image_id_bytes(&image_id.replace(' ', "")).unwrap(), expected());
+
+        assert!(parse_image_id_bytes("[1, 2, 3, 4, 5, 6, 7]").is_err());
+        assert!(parse_image_id_bytes("[1, 2, 3, 4, 5, 6, 7, 8, 9]").is_err());
+        assert!(parse_image_id_bytes("[1, 2, 3, 4, 5, 6, 7, 4294967296]").is_err());
+        assert!(parse_image_id_bytes("[1, 2, 3, 4, 5, 6, 7, 8").is_err());
+    }
+
+    #[test]
+    fn test_forms_round_trip() {
+        let forms = ImageIdForms::new(&expected());
+        assert_eq!(forms.hex, HEX);
+        assert_eq!(parse_image_id_bytes(&forms.hex).unwrap(), expected());
+        assert_eq!(parse_image_id_bytes(&forms.base64).unwrap(), expected());
+        assert_eq!(parse_image_id_bytes(&format!("{:?}", forms.words)).unwrap(), expected());
+    }
+
+    #[test]
+    fn test_reject_invalid_lengths() {
+        // Too long, used to panic
+        assert!(parse_image_id_bytes(&format!("{}00", HEX)).is_err());
+        assert!(parse_image_id_bytes(&format!("0x{}00", HEX)).is_err());
+        // Too short or odd length, used to be silently padded
+        assert!(parse_image_id_bytes(&HEX[2..]).is_err());
+        assert!(parse_image_id_bytes(&format!("0x{}", &HEX[1..])).is_err());
+        assert!(parse_image_id_bytes(&format!("0x{}", &HEX[..63])).is_err());
+        // Hexadecimal digits at the length of unpadded base64
+        assert!(parse_image_id_bytes(&HEX[..43]).is_err());
+        assert!(parse_image_id_bytes(&HEX[..44]).is_err());
+        // Not hexadecimal
+        assert!(parse_image_id_bytes(&format!("0x{}zz", &HEX[2..])).is_err());
+        assert!(parse_image_id_bytes("").is_err());
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+use std::str::FromStr;
+
+use risc0_zkvm::{InnerReceipt, Receipt};
+use serde::Serialize;
+
+/// Kinds of RISC Zero receipts, by the proof they carry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiptKind {
+    /// One STARK proof per segment of the execution.
+    Composite,
+    /// A single STARK proof, recursively compressed from the segments.
+    Succinct,
+    /// A Groth16 SNARK wrapping a succinct receipt.
+    Groth16,
+    /// No proof, made by the prover in dev mode.
+    Fake,
+}
+
+impl ReceiptKind {
+    pub const ALL: [ReceiptKind; 4] = [
+        ReceiptKind::Composite,
+        ReceiptKind::Succinct,
+        ReceiptKind::Groth16,
+        ReceiptKind::Fake,
+    ];
+
+    pub fn of(receipt: &Receipt) -> ReceiptKind {
+        match &receipt.inner {
+            InnerReceipt::Composite(_) => ReceiptKind::Composite,
+            InnerReceipt::Succinct(_) => ReceiptKind::Succinct,
+            InnerReceipt::Compact(_) => ReceiptKind::Groth16,
+            InnerReceipt::Fake { .. } => ReceiptKind::Fake,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReceiptKind::Composite => "composite",
+            ReceiptKind::Succinct => "succinct",
+            ReceiptKind::Groth16 => "groth16",
+            ReceiptKind::Fake => "fake",
+        }
+    }
+}
+
+impl fmt::Display for ReceiptKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ReceiptKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ReceiptKind::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .ok_or_else(|| {
+                let kinds: Vec<&str> = ReceiptKind::ALL.iter().map(ReceiptKind::as_str).collect();
+                format!("unknown receipt kind '{}', expected one of {}", s, kinds.join(", "))
+            })
+    }
+}
+
+/// Checks the kind is one of the accepted ones, any kind is accepted when none are given.
+pub fn check_kind(kind: ReceiptKind, accepted: &[ReceiptKind]) -> Result<(), String> {
+    if accepted.is_empty() || accepted.contains(&kind) {
+        return Ok(());
+    }
+    let accepted: Vec<&str> = accepted.iter().map(ReceiptKind::as_str).collect();
+    Err(format!("{} receipts are not accepted, expected {}", kind, accepted.join(" or ")))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_kind, ReceiptKind};
+
+    #[test]
+    fn test_parse_kind() {
+        for kind in ReceiptKind::ALL {
+            assert_eq!(kind.as_str().parse::<ReceiptKind>().unwrap(), kind);
+            assert_eq!(serde_json::to_value(kind).unwrap(), kind.as_str());
+        }
+        assert!("compact".parse::<ReceiptKind>().is_err());
+    }
+
+    #[test]
+    fn test_check_kind() {
+        assert!(check_kind(ReceiptKind::Composite, &[]).is_ok());
+        assert!(check_kind(ReceiptKind::Groth16, &[ReceiptKind::Succinct, ReceiptKind::Groth16]).is_ok());
+        assert!(check_kind(ReceiptKind::Composite, &[ReceiptKind::Succinct, ReceiptKind::Groth16]).is_err());
+    }
+}
@@ -0,0 +1,314 @@
+use base64::prelude::*;
+use bincode::Options;
+use hyle_contract::HyleOutput;
+use hyle_verifier_core::{extra_input_str, schema, to_json_output, Verifier, VerifyError};
+use risc0_zkvm::{sha::Digest, Receipt};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub use crate::claim::{
+    assumption_digests, check_claim, hex, inspect_claim, verify_claim, ClaimInfo, VerifiedClaim,
+};
+pub use crate::error::Error;
+pub use crate::image_id::ImageIdForms;
+pub use crate::kind::{check_kind, ReceiptKind};
+pub use crate::version::{parse_any_receipt, verify_any_receipt, AnyReceipt, Risc0Version};
+pub use hyle_verifier_core::schema::{FieldDecode, FieldType, Schema};
+
+mod claim;
+mod error;
+mod image_id;
+mod kind;
+#[cfg(feature = "risc0-1")]
+mod v1;
+mod version;
+
+/// HyleOutput of a verified receipt, along with what was verified.
+#[derive(Serialize, Debug)]
+pub struct Risc0Output<T> {
+    #[serde(flatten)]
+    pub output: HyleOutput<T>,
+    /// Release of risc0 the receipt was verified with.
+    pub risc0_version: Risc0Version,
+    pub receipt_kind: ReceiptKind,
+    /// Hex digests of the claims of the receipts the guest verified, resolved when verifying.
+    pub assumptions: Vec<String>,
+    /// Set when a fake receipt was accepted in dev mode: the output is not proven, it must
+    /// not be used in production.
+    pub dev_mode: bool,
+}
+
+/// Checks the receipt is of an accepted kind, any kind is accepted when none are given.
+pub fn check_receipt_kind(receipt: &AnyReceipt, accepted: &[ReceiptKind]) -> Result<ReceiptKind, Error> {
+    let kind = receipt.kind()?;
+    check_kind(kind, accepted).map_err(Error::ReceiptKind)?;
+    Ok(kind)
+}
+
+/// Verifies the receipt for the given image id, and decodes its journal as an HyleOutput.
+/// Its assumptions must be resolved and fake receipts are rejected, see [verify_claim] to
+/// resolve assumptions with other receipts or accept fake receipts.
+pub fn verify_receipt<T: DeserializeOwned>(
+    image_id: Digest,
+    receipt: &Receipt,
+) -> Result<HyleOutput<T>, Error> {
+    verify_claim(image_id, receipt, &[], false)?;
+    receipt.journal.decode().map_err(Error::JournalDecode)
+}
+
+/// Verifies the receipt for the given image id, and decodes its journal as an HyleOutput
+/// whose program outputs follow the schema.
+pub fn verify_receipt_with_schema(
+    image_id: Digest,
+    receipt: &Receipt,
+    schema: &Schema,
+) -> Result<HyleOutput<serde_json::Value>, Error> {
+    verify_claim(image_id, receipt, &[], false)?;
+    decode_journal(&receipt.journal.bytes, schema)
+}
+
+/// Decodes a journal as an HyleOutput.
+pub fn decode_output<T: DeserializeOwned>(journal: &[u8]) -> Result<HyleOutput<T>, Error> {
+    risc0_zkvm::serde::from_slice(journal).map_err(Error::JournalDecode)
+}
+
+/// Decodes a journal as an HyleOutput whose program outputs follow the schema.
+pub fn decode_journal(
+    journal: &[u8],
+    schema: &Schema,
+) -> Result<HyleOutput<serde_json::Value>, Error> {
+    let words = journal_words(journal)?;
+    let mut reader = words.as_slice();
+    let mut deserializer = risc0_zkvm::serde::Deserializer::new(&mut reader);
+    let output = schema::decode_with_schema(&mut deserializer, schema).map_err(Error::JournalDecode)?;
+    // Words left over are fields missing from the schema, which would otherwise be ignored
+    if !reader.is_empty() {
+        return Err(Error::JournalDecode(risc0_zkvm::serde::Error::Custom(format!(
+            "{} words left after the outputs of the schema",
+            reader.len()
+        ))));
+    }
+    Ok(output)
+}
+
+/// The journal is made of the words written by the guest.
+fn journal_words(journal: &[u8]) -> Result<Vec<u32>, Error> {
+    if journal.len() % 4 != 0 {
+        return Err(Error::JournalDecode(
+            risc0_zkvm::serde::Error::DeserializeUnexpectedEnd,
+        ));
+    }
+    Ok(journal
+        .chunks_exact(4)
+        .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+        .collect())
+}
+
+/// Raw journal of a receipt, and how far it decodes as an HyleOutput.
+#[derive(Serialize, Debug)]
+pub struct JournalDump {
+    pub length: usize,
+    pub hex: String,
+    pub base64: String,
+    pub fields: Vec<FieldDecode>,
+}
+
+/// Dumps the journal and decodes its HyleOutput fields one by one, to debug guest commits.
+pub fn dump_journal(journal: &[u8], schema: Option<&Schema>) -> JournalDump {
+    let fields = match journal_words(journal) {
+        Ok(words) => {
+            let mut deserializer = risc0_zkvm::serde::Deserializer::new(words.as_slice());
+            schema::decode_fields(&mut deserializer, schema)
+        }
+        Err(_) => vec![FieldDecode {
+            field: "version",
+            value: None,
+            error: Some(format!("the journal length {} is not a multiple of 4", journal.len())),
+        }],
+    };
+    JournalDump {
+        length: journal.len(),
+        hex: journal.iter().map(|byte| format!("{:02x}", byte)).collect(),
+        base64: BASE64_STANDARD.encode(journal),
+        fields,
+    }
+}
+
+/// Serialization formats of receipts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReceiptFormat {
+    Json,
+    Bincode,
+}
+
+impl ReceiptFormat {
+    /// JSON receipts are objects, anything else is assumed to be bincode.
+    pub fn detect(receipt: &[u8]) -> ReceiptFormat {
+        match receipt.iter().find(|c| !c.is_ascii_whitespace()) {
+            Some(b'{') => ReceiptFormat::Json,
+            _ => ReceiptFormat::Bincode,
+        }
+    }
+}
+
+/// Deserializes a JSON or bincode receipt, detecting the format from its content.
+pub fn parse_receipt(receipt: &[u8]) -> Result<Receipt, Error> {
+    parse_receipt_with_format(receipt, ReceiptFormat::detect(receipt))
+}
+
+pub fn parse_receipt_with_format(receipt: &[u8], format: ReceiptFormat) -> Result<Receipt, Error> {
+    match format {
+        ReceiptFormat::Json => serde_json::from_slice(receipt)
+            .map_err(|err| Error::InvalidReceipt(format!("invalid JSON receipt: {}", err))),
+        // Trailing bytes are rejected, as they could be a receipt of another version
+        ReceiptFormat::Bincode => bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .deserialize(receipt)
+            .map_err(|err| Error::InvalidReceipt(format!("invalid bincode receipt: {}", err))),
+    }
+}
+
+/// Computes the image id of a guest from its ELF.
+pub fn image_id_from_elf(elf: &[u8]) -> Result<Digest, Error> {
+    risc0_zkvm::compute_image_id(elf).map_err(|err| Error::InvalidElf(err.to_string()))
+}
+
+/// Parses an image id given as 0x-prefixed or bare hex, base64, or `[u32; 8]` words.
+pub fn parse_image_id(image_id: &str) -> Result<Digest, Error> {
+    image_id::parse_image_id_bytes(image_id)
+        .map(Digest::from)
+        .map_err(Error::InvalidImageId)
+}
+
+pub struct Risc0Verifier;
+
+impl Verifier for Risc0Verifier {
+    fn name(&self) -> &'static str {
+        "risc0"
+    }
+
+    /// `program_id` is the image id of the guest, `proof` a JSON or bincode serialized receipt.
+    /// The program outputs are decoded when `extra_inputs` has an `outputs_schema`, and the
+    /// receipt kind is checked when it has `accept_kinds`. Base64 receipts in `assumptions`
+    /// resolve the assumptions of the receipt. The risc0 version of the receipt is detected
+    /// unless `risc0_version` is given.
+    fn verify(
+        &self,
+        program_id: &str,
+        proof: &[u8],
+        extra_inputs: &serde_json::Value,
+    ) -> Result<HyleOutput<serde_json::Value>, VerifyError> {
+        let version = match extra_input_str(extra_inputs, "risc0_version")? {
+            Some(version) => Some(version.parse().map_err(|err| {
+                VerifyError::MalformedProof(format!("Invalid risc0_version: {}", err))
+            })?),
+            None => None,
+        };
+        let receipt = parse_any_receipt(proof, ReceiptFormat::detect(proof), version)?;
+        let image_id = parse_image_id(program_id)?;
+        if let Some(kinds) = extra_inputs.get("accept_kinds") {
+            let kinds: Vec<ReceiptKind> = serde_json::from_value::<Vec<String>>(kinds.clone())
+                .map_err(|err| err.to_string())
+                .and_then(|kinds| kinds.iter().map(|kind| kind.parse()).collect())
+                .map_err(|err| VerifyError::MalformedProof(format!("Invalid accept_kinds: {}", err)))?;
+            check_receipt_kind(&receipt, &kinds)?;
+        }
+        let assumption_receipts = match extra_inputs.get("assumptions") {
+            Some(receipts) => serde_json::from_value::<Vec<String>>(receipts.clone())
+                .map_err(|err| {
+                    VerifyError::MalformedProof(format!("Invalid assumptions: {}", err))
+                })?
+                .iter()
+                .map(|receipt| {
+                    let receipt = BASE64_STANDARD.decode(receipt).map_err(|err| {
+                        VerifyError::MalformedProof(format!("Invalid assumption receipt: {}", err))
+                    })?;
+                    Ok(parse_receipt(&receipt)?)
+                })
+                .collect::<Result<Vec<_>, VerifyError>>()?,
+            None => vec![],
+        };
+        verify_any_receipt(image_id, &receipt, &assumption_receipts, false)?;
+        match extra_input_str(extra_inputs, "outputs_schema")? {
+            Some(schema) => {
+                let schema: Schema = schema.parse().map_err(|err| {
+                    VerifyError::MalformedProof(format!("Invalid outputs schema: {}", err))
+                })?;
+                Ok(decode_journal(receipt.journal(), &schema)?)
+            }
+            None => {
+                let output: HyleOutput<()> = decode_output(receipt.journal())?;
+                to_json_output(output)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use hyle_contract::HyleOutput;
+
+    use super::{
+        check_receipt_kind, decode_journal, parse_image_id, parse_receipt, verify_receipt, AnyReceipt,
+        ReceiptKind,
+    };
+
+    // Receipts of the same guest, proven as each kind, and its image id.
+    fn fixture(name: &str) -> Vec<u8> {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("example").join(name);
+        std::fs::read(&path).unwrap_or_else(|err| panic!("Failed to read {}: {}", path.display(), err))
+    }
+
+    fn check_fixture(name: &str, kind: ReceiptKind) {
+        let image_id = parse_image_id(String::from_utf8(fixture("image_id")).unwrap().trim()).unwrap();
+        let receipt = parse_receipt(&fixture(name)).unwrap();
+        verify_receipt::<()>(image_id, &receipt).unwrap();
+
+        let receipt = AnyReceipt::V0_21(receipt);
+        assert_eq!(check_receipt_kind(&receipt, &[kind]).unwrap(), kind);
+        let others: Vec<ReceiptKind> = ReceiptKind::ALL.into_iter().filter(|k| *k != kind).collect();
+        assert!(check_receipt_kind(&receipt, &others).is_err());
+    }
+
+    #[test]
+    fn test_decode_journal() {
+        let output = HyleOutput {
+            version: 1,
+            initial_state: vec![1, 2],
+            next_state: vec![3],
+            origin: "alice".to_string(),
+            caller: "bob".to_string(),
+            block_number: 4,
+            block_time: 5,
+            tx_hash: vec![6],
+            program_outputs: ("carol".to_string(), 10u64),
+        };
+        let words = risc0_zkvm::serde::to_vec(&output).unwrap();
+        let journal: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+
+        let decoded = decode_journal(&journal, &"to:string,amount:u64".parse().unwrap()).unwrap();
+        assert_eq!(decoded.program_outputs, serde_json::json!({"to": "carol", "amount": 10}));
+        // The guest committed one field more than the schema has
+        assert!(decode_journal(&journal, &"to:string".parse().unwrap()).is_err());
+    }
+
+    #[test]
+    #[ignore = "needs example/composite.receipt from a RISC Zero prover"]
+    fn test_composite_receipt() {
+        check_fixture("composite.receipt", ReceiptKind::Composite);
+    }
+
+    #[test]
+    #[ignore = "needs example/succinct.receipt from a RISC Zero prover"]
+    fn test_succinct_receipt() {
+        check_fixture("succinct.receipt", ReceiptKind::Succinct);
+    }
+
+    #[test]
+    #[ignore = "needs example/groth16.receipt from a RISC Zero prover"]
+    fn test_groth16_receipt() {
+        check_fixture("groth16.receipt", ReceiptKind::Groth16);
+    }
+}
@@ -1,42 +1,213 @@
-use serde_json;
-use std::env;
+use std::io::Read;
 
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use hyle_contract::HyleOutput;
+use hyle_verifier_core::{read_file, VerifyError};
+use risc0_verifier::{
+    check_receipt_kind, decode_journal, decode_output, dump_journal, hex, image_id_from_elf,
+    inspect_claim, parse_any_receipt, parse_image_id, parse_receipt, parse_receipt_with_format,
+    verify_any_receipt, AnyReceipt, ClaimInfo, Error, ImageIdForms, JournalDump, ReceiptFormat,
+    ReceiptKind, Risc0Output, Risc0Version, Schema, VerifiedClaim,
+};
+use risc0_zkvm::Receipt;
+use serde::Serialize;
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Format {
+    /// Detect the format from the receipt content
+    Auto,
+    Json,
+    Bincode,
+}
+
+#[derive(Parser, Debug)]
+#[clap(about = "Verify a RISC Zero receipt and print its HyleOutput")]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+struct Cli {
+    #[clap(subcommand)]
+    command: Option<Command>,
+    #[clap(flatten)]
+    verify: VerifyArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    #[clap(about = "Print the claim of a receipt, without verifying it")]
+    Inspect(InspectArgs),
+    #[clap(about = "Print the image id of a guest ELF, in every accepted form")]
+    ImageId(ImageIdArgs),
+}
+
+#[derive(Args, Debug)]
+struct VerifyArgs {
+    /// Image id, as hex (with or without 0x), base64 or [u32; 8] words
+    #[clap(required = true)]
+    image_id: Option<String>,
+    /// Receipt file, or - to read it from stdin
+    #[clap(required = true)]
+    receipt_path: Option<String>,
+    /// Serialization format of the receipt
+    #[clap(long, value_enum, default_value_t = Format::Auto)]
+    format: Format,
+    /// Release of risc0 which made the receipt, 0.21 or 1 (with the risc0-1 feature).
+    /// Detected from the receipt by default
+    #[clap(long)]
+    risc0_version: Option<Risc0Version>,
+    /// Decode the program outputs as these fields, e.g. "from:string,to:string,amount:u64".
+    /// Types are bool, u8, u16, u32, u64, i8, i16, i32, i64, string and bytes.
+    #[clap(long)]
+    outputs_schema: Option<Schema>,
+    /// Only accept these kinds of receipts: composite, succinct, groth16 or fake.
+    /// All kinds are accepted by default, fake receipts need --allow-dev-mode too.
+    #[clap(long, value_delimiter = ',')]
+    accept_kinds: Vec<ReceiptKind>,
+    /// Receipt resolving an assumption of the receipt, can be repeated.
+    /// Without them, the assumptions of the receipt must already be resolved.
+    #[clap(long = "assumption")]
+    assumptions: Vec<String>,
+    /// Once verified, print the raw journal and its HyleOutput fields decoded one by one
+    /// instead of the HyleOutput, to debug what the guest commits
+    #[clap(long)]
+    dump_journal: bool,
+    /// Accept fake receipts made by provers in dev mode, for local testnets.
+    /// The output is marked with "dev_mode": true, it must not be used in production
+    #[clap(long)]
+    allow_dev_mode: bool,
+}
+
+#[derive(Args, Debug)]
+struct InspectArgs {
+    /// Receipt file, or - to read it from stdin
+    receipt_path: String,
+    /// Serialization format of the receipt
+    #[clap(long, value_enum, default_value_t = Format::Auto)]
+    format: Format,
+}
+
+#[derive(Args, Debug)]
+struct ImageIdArgs {
+    /// Guest ELF file
+    elf_path: String,
+}
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() != 3 {
-        eprintln!("Usage: {} <image_id> <receipt_path>", args[0]);
-        std::process::exit(1);
+    let args = Cli::parse();
+    // Fake receipts are accepted with --allow-dev-mode only, which verify_claim decides on.
+    // risc0's own verification accepts them when RISC0_DEV_MODE is set, so an inherited value
+    // is cleared in case a receipt reaches it.
+    std::env::remove_var("RISC0_DEV_MODE");
+
+    // Outputs to stdout for the caller to read.
+    let output = match &args.command {
+        Some(Command::Inspect(args)) => inspect(args).map(|info| serde_json::to_string(&info)),
+        Some(Command::ImageId(args)) => image_id_forms(args).map(|forms| serde_json::to_string(&forms)),
+        None if args.verify.dump_journal => dump(&args.verify).map(|report| serde_json::to_string(&report)),
+        None => verify(&args.verify).map(|output| serde_json::to_string(&output)),
+    };
+    match output {
+        Ok(output) => println!("{}", output.expect("Failed to serialize output")),
+        Err(err) => err.exit(),
     }
-    
-    // Image ID is the hexademical representation of the method ID, without leading prefix.
-    let image_id = &args[1];
-
-    // Parse the proof from file
-    let receipt_path = &args[2];
-    let receipt_content = std::fs::read_to_string(receipt_path).expect("Failed to read receipt file");
-    let receipt: risc0_zkvm::Receipt = serde_json::from_str(&receipt_content).expect("Failed to parse receipt file");
-
-    let mut decoded_image_id: [u8; 32] = [0; 32];
-    for i in 0..image_id.len() / 2 {
-        decoded_image_id[i] = u8::from_str_radix(
-            &image_id
-                .get(i*2..i*2 + 2)
-                .expect("Invalid method ID string"),
-            16,
-        )
-        .expect("Invalid method ID string")
+}
+
+fn verify(args: &VerifyArgs) -> Result<Risc0Output<serde_json::Value>, VerifyError> {
+    let (receipt, receipt_kind, verified) = verified_receipt(args)?;
+    let output = match &args.outputs_schema {
+        Some(schema) => decode_journal(receipt.journal(), schema).map_err(with_dump_hint)?,
+        None => {
+            let output: HyleOutput<()> = decode_output(receipt.journal()).map_err(with_dump_hint)?;
+            hyle_verifier_core::to_json_output(output)?
+        }
+    };
+    Ok(Risc0Output {
+        output,
+        risc0_version: receipt.version(),
+        receipt_kind,
+        assumptions: verified.assumptions.iter().map(hex).collect(),
+        dev_mode: verified.dev_mode,
+    })
+}
+
+fn with_dump_hint(err: Error) -> VerifyError {
+    match VerifyError::from(err) {
+        VerifyError::OutputDecode(msg) => VerifyError::OutputDecode(format!(
+            "{}, the receipt is valid, run with --dump-journal to see the journal",
+            msg
+        )),
+        err => err,
     }
-    // Rotate to pad 0s in front.
-    decoded_image_id.rotate_right((64 - image_id.len()) / 2);
+}
 
-    // perform verification
-    receipt
-        .verify(decoded_image_id)
-        .expect("Verification failed");
+/// Journal of a verified receipt, printed with --dump-journal.
+#[derive(Serialize, Debug)]
+struct JournalReport {
+    risc0_version: Risc0Version,
+    receipt_kind: ReceiptKind,
+    assumptions: Vec<String>,
+    dev_mode: bool,
+    journal: JournalDump,
+}
 
-    // Outputs to stdout for the caller to read.
-    let output: HyleOutput<()> = receipt.journal.decode().expect("Failed to decode receipt journal");
-    println!("{}", serde_json::to_string(&output).expect("Failed to serialize output"));
+fn dump(args: &VerifyArgs) -> Result<JournalReport, VerifyError> {
+    let (receipt, receipt_kind, verified) = verified_receipt(args)?;
+    Ok(JournalReport {
+        risc0_version: receipt.version(),
+        receipt_kind,
+        assumptions: verified.assumptions.iter().map(hex).collect(),
+        dev_mode: verified.dev_mode,
+        journal: dump_journal(receipt.journal(), args.outputs_schema.as_ref()),
+    })
+}
+
+/// Loads and verifies the receipt, returning its kind and what was verified.
+fn verified_receipt(args: &VerifyArgs) -> Result<(AnyReceipt, ReceiptKind, VerifiedClaim), VerifyError> {
+    let (Some(image_id), Some(receipt_path)) = (&args.image_id, &args.receipt_path) else {
+        unreachable!("clap requires both without a subcommand");
+    };
+    let receipt = read_receipt(receipt_path)?;
+    let receipt = parse_any_receipt(&receipt, receipt_format(&receipt, args.format), args.risc0_version)?;
+    let image_id = parse_image_id(image_id)?;
+    let receipt_kind = check_receipt_kind(&receipt, &args.accept_kinds)?;
+    let assumption_receipts = args
+        .assumptions
+        .iter()
+        .map(|path| Ok(parse_receipt(&read_file(path)?)?))
+        .collect::<Result<Vec<_>, VerifyError>>()?;
+    let verified = verify_any_receipt(image_id, &receipt, &assumption_receipts, args.allow_dev_mode)?;
+    Ok((receipt, receipt_kind, verified))
+}
+
+fn inspect(args: &InspectArgs) -> Result<ClaimInfo, VerifyError> {
+    let receipt = load_receipt(&args.receipt_path, args.format)?;
+    Ok(inspect_claim(&receipt)?)
+}
+
+fn image_id_forms(args: &ImageIdArgs) -> Result<ImageIdForms, VerifyError> {
+    let image_id = image_id_from_elf(&read_file(&args.elf_path)?)?;
+    let image_id = image_id.as_bytes().try_into().expect("digests are 32 bytes");
+    Ok(ImageIdForms::new(image_id))
+}
+
+fn load_receipt(receipt_path: &str, format: Format) -> Result<Receipt, VerifyError> {
+    let receipt = read_receipt(receipt_path)?;
+    Ok(parse_receipt_with_format(&receipt, receipt_format(&receipt, format))?)
+}
+
+fn receipt_format(receipt: &[u8], format: Format) -> ReceiptFormat {
+    match format {
+        Format::Auto => ReceiptFormat::detect(receipt),
+        Format::Json => ReceiptFormat::Json,
+        Format::Bincode => ReceiptFormat::Bincode,
+    }
+}
+
+fn read_receipt(receipt_path: &str) -> Result<Vec<u8>, VerifyError> {
+    if receipt_path != "-" {
+        return read_file(receipt_path);
+    }
+    let mut receipt = vec![];
+    std::io::stdin()
+        .read_to_end(&mut receipt)
+        .map_err(|err| VerifyError::Io(format!("Failed to read receipt from stdin: {}", err)))?;
+    Ok(receipt)
 }
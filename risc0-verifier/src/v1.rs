@@ -0,0 +1,51 @@
+//! Receipts of risc0 1, verified with the risc0-zkvm 1 release.
+
+use bincode::Options;
+pub use risc0_zkvm_1::Receipt;
+use risc0_zkvm_1::{sha::Digest, InnerReceipt};
+
+use crate::error::Error;
+use crate::kind::ReceiptKind;
+use crate::ReceiptFormat;
+
+pub fn parse_receipt(receipt: &[u8], format: ReceiptFormat) -> Result<Receipt, Error> {
+    match format {
+        ReceiptFormat::Json => serde_json::from_slice(receipt)
+            .map_err(|err| Error::InvalidReceipt(format!("invalid JSON receipt: {}", err))),
+        ReceiptFormat::Bincode => bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .deserialize(receipt)
+            .map_err(|err| Error::InvalidReceipt(format!("invalid bincode receipt: {}", err))),
+    }
+}
+
+pub fn kind(receipt: &Receipt) -> Result<ReceiptKind, Error> {
+    // Later 1.x releases may add kinds
+    #[allow(unreachable_patterns)]
+    match &receipt.inner {
+        InnerReceipt::Composite(_) => Ok(ReceiptKind::Composite),
+        InnerReceipt::Succinct(_) => Ok(ReceiptKind::Succinct),
+        InnerReceipt::Groth16(_) => Ok(ReceiptKind::Groth16),
+        InnerReceipt::Fake(_) => Ok(ReceiptKind::Fake),
+        _ => Err(Error::InvalidReceipt(
+            "unsupported kind of risc0 1 receipt".to_string(),
+        )),
+    }
+}
+
+/// Verifies the receipt, which checks its claim is for the image id, with a guest which
+/// halted with exit code 0 and committed the journal, without unresolved assumptions.
+/// Fake receipts are rejected without looking at `RISC0_DEV_MODE`.
+pub fn verify(image_id: &[u8], receipt: &Receipt) -> Result<(), Error> {
+    if kind(receipt)? == ReceiptKind::Fake {
+        return Err(Error::ReceiptKind(
+            "fake risc0 1 receipts are not accepted, even in dev mode".to_string(),
+        ));
+    }
+    let image_id: [u8; 32] = image_id
+        .try_into()
+        .map_err(|_| Error::InvalidImageId("expected 32 bytes".to_string()))?;
+    receipt
+        .verify(Digest::from(image_id))
+        .map_err(|err| Error::VerificationV1(err.to_string()))
+}
@@ -0,0 +1,185 @@
+use std::fmt;
+use std::str::FromStr;
+
+use risc0_zkvm::{sha::Digest, Receipt};
+use serde::Serialize;
+
+use crate::claim::{verify_claim, VerifiedClaim};
+use crate::error::Error;
+use crate::kind::ReceiptKind;
+use crate::{parse_receipt_with_format, ReceiptFormat};
+#[cfg(feature = "risc0-1")]
+use crate::v1;
+
+/// Releases of risc0 whose receipts can be verified, each with its own risc0-zkvm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Risc0Version {
+    #[serde(rename = "0.21")]
+    V0_21,
+    /// Needs the `risc0-1` feature.
+    #[cfg(feature = "risc0-1")]
+    #[serde(rename = "1")]
+    V1,
+}
+
+impl Risc0Version {
+    pub const ALL: &'static [Risc0Version] = &[
+        Risc0Version::V0_21,
+        #[cfg(feature = "risc0-1")]
+        Risc0Version::V1,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Risc0Version::V0_21 => "0.21",
+            #[cfg(feature = "risc0-1")]
+            Risc0Version::V1 => "1",
+        }
+    }
+}
+
+impl fmt::Display for Risc0Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Risc0Version {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(version) = Risc0Version::ALL.iter().find(|version| version.as_str() == s) {
+            return Ok(*version);
+        }
+        if s == "1" {
+            return Err("risc0 1 receipts need the risc0-1 feature".to_string());
+        }
+        let versions: Vec<&str> = Risc0Version::ALL.iter().map(Risc0Version::as_str).collect();
+        Err(format!("unknown risc0 version '{}', expected one of {}", s, versions.join(", ")))
+    }
+}
+
+/// A receipt of one of the supported risc0 versions.
+pub enum AnyReceipt {
+    V0_21(Receipt),
+    #[cfg(feature = "risc0-1")]
+    V1(v1::Receipt),
+}
+
+impl AnyReceipt {
+    pub fn version(&self) -> Risc0Version {
+        match self {
+            AnyReceipt::V0_21(_) => Risc0Version::V0_21,
+            #[cfg(feature = "risc0-1")]
+            AnyReceipt::V1(_) => Risc0Version::V1,
+        }
+    }
+
+    pub fn kind(&self) -> Result<ReceiptKind, Error> {
+        match self {
+            AnyReceipt::V0_21(receipt) => Ok(ReceiptKind::of(receipt)),
+            #[cfg(feature = "risc0-1")]
+            AnyReceipt::V1(receipt) => v1::kind(receipt),
+        }
+    }
+
+    pub fn journal(&self) -> &[u8] {
+        match self {
+            AnyReceipt::V0_21(receipt) => &receipt.journal.bytes,
+            #[cfg(feature = "risc0-1")]
+            AnyReceipt::V1(receipt) => &receipt.journal.bytes,
+        }
+    }
+}
+
+/// Deserializes a receipt of the given risc0 version, or of the version detected from it.
+///
+/// JSON receipts of risc0 1 have a `metadata` field that earlier receipts lack. Bincode
+/// receipts have no such marker, each version is tried in turn and the first one which reads
+/// the whole receipt is used.
+pub fn parse_any_receipt(
+    receipt: &[u8],
+    format: ReceiptFormat,
+    version: Option<Risc0Version>,
+) -> Result<AnyReceipt, Error> {
+    let version = match (version, format) {
+        (Some(version), _) => version,
+        (None, ReceiptFormat::Json) => detect_json_version(receipt)?,
+        (None, ReceiptFormat::Bincode) => {
+            let mut errors = vec![];
+            for version in Risc0Version::ALL {
+                match parse_versioned(receipt, format, *version) {
+                    Ok(receipt) => return Ok(receipt),
+                    Err(err) => errors.push(format!("as risc0 {}: {}", version, err)),
+                }
+            }
+            return Err(Error::InvalidReceipt(errors.join(", ")));
+        }
+    };
+    parse_versioned(receipt, format, version)
+}
+
+fn detect_json_version(receipt: &[u8]) -> Result<Risc0Version, Error> {
+    let receipt: serde_json::Value = serde_json::from_slice(receipt)
+        .map_err(|err| Error::InvalidReceipt(format!("invalid JSON receipt: {}", err)))?;
+    if receipt.get("metadata").is_none() {
+        return Ok(Risc0Version::V0_21);
+    }
+    "1".parse().map_err(Error::InvalidReceipt)
+}
+
+fn parse_versioned(
+    receipt: &[u8],
+    format: ReceiptFormat,
+    version: Risc0Version,
+) -> Result<AnyReceipt, Error> {
+    match version {
+        Risc0Version::V0_21 => parse_receipt_with_format(receipt, format).map(AnyReceipt::V0_21),
+        #[cfg(feature = "risc0-1")]
+        Risc0Version::V1 => v1::parse_receipt(receipt, format).map(AnyReceipt::V1),
+    }
+}
+
+/// Verifies a receipt of any supported version, see [verify_claim].
+///
+/// Assumption receipts and dev mode are only supported for risc0 0.21 receipts, risc0 1
+/// receipts must have their assumptions resolved when proving, and fake ones are rejected.
+pub fn verify_any_receipt(
+    image_id: Digest,
+    receipt: &AnyReceipt,
+    assumption_receipts: &[Receipt],
+    allow_dev_mode: bool,
+) -> Result<VerifiedClaim, Error> {
+    match receipt {
+        AnyReceipt::V0_21(receipt) => {
+            verify_claim(image_id, receipt, assumption_receipts, allow_dev_mode)
+        }
+        #[cfg(feature = "risc0-1")]
+        AnyReceipt::V1(receipt) => {
+            if !assumption_receipts.is_empty() {
+                return Err(Error::Claim(
+                    "assumption receipts are only supported for risc0 0.21 receipts".to_string(),
+                ));
+            }
+            v1::verify(image_id.as_bytes(), receipt)?;
+            Ok(VerifiedClaim {
+                assumptions: vec![],
+                dev_mode: false,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Risc0Version;
+
+    #[test]
+    fn test_parse_version() {
+        for version in Risc0Version::ALL {
+            assert_eq!(version.as_str().parse::<Risc0Version>().unwrap(), *version);
+            assert_eq!(serde_json::to_value(version).unwrap(), version.as_str());
+        }
+        assert!("0.20".parse::<Risc0Version>().is_err());
+    }
+}
@@ -0,0 +1,329 @@
+use std::path::Path;
+use std::sync::OnceLock;
+
+use base64::prelude::*;
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use sp1_sdk::{ProverClient, SP1VerifyingKey};
+
+use hyle_contract::HyleOutput;
+use hyle_verifier_core::schema::{self, Schema};
+use hyle_verifier_core::{extra_input_str, to_json_output, Verifier, VerifyError};
+
+mod mode;
+
+pub use mode::{check_mode, parse_proof, AnyProof, ProofMode};
+
+/// HyleOutput of a verified proof, along with the hash of its verification key and its mode.
+#[derive(Serialize, Debug)]
+pub struct Sp1Output<T> {
+    #[serde(flatten)]
+    pub output: HyleOutput<T>,
+    pub vk_hash: String,
+    pub proof_mode: ProofMode,
+    /// Public values left after the HyleOutput, the program outputs when they are not decoded.
+    pub trailing_bytes: usize,
+}
+
+/// Client shared by every verification, as setting it up loads the verifier of each mode.
+fn prover_client() -> &'static ProverClient {
+    static PROVER_CLIENT: OnceLock<ProverClient> = OnceLock::new();
+    PROVER_CLIENT.get_or_init(ProverClient::new)
+}
+
+/// Verifies the proof, of any mode as saved by its `save` method, with the verification key.
+/// Proofs of modes which are not accepted are rejected, all are accepted when none are given.
+/// The program outputs are decoded when a schema is given, see [decode_public_values].
+pub fn verify_proof(
+    vk: &SP1VerifyingKey,
+    proof: &[u8],
+    accept_modes: &[ProofMode],
+    schema: Option<&Schema>,
+) -> Result<Sp1Output<Value>, VerifyError> {
+    let mut failure = None;
+    for proof in parse_proof(proof, accept_modes)? {
+        if let Err(err) = proof.verify(prover_client(), vk) {
+            failure.get_or_insert(err);
+            continue;
+        }
+        let (output, trailing_bytes) =
+            decode_public_values(proof.public_values().as_slice(), schema)?;
+        return Ok(Sp1Output {
+            output,
+            vk_hash: vk_hash(vk)?,
+            proof_mode: proof.mode(),
+            trailing_bytes,
+        });
+    }
+    Err(failure.expect("parse_proof returns at least one proof"))
+}
+
+/// Decodes the HyleOutput committed by the program from the public values, returning an
+/// error on malformed ones rather than panicking like `SP1PublicValues::read`.
+/// With a schema, the program outputs are decoded and must be the end of the public values.
+/// Without it, they are left undecoded: the number of bytes left is returned with the output.
+pub fn decode_public_values(
+    public_values: &[u8],
+    schema: Option<&Schema>,
+) -> Result<(HyleOutput<Value>, usize), VerifyError> {
+    // A length prefix can not claim more than what is left of the public values
+    let options = bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_limit(public_values.len() as u64);
+    let mut reader = public_values;
+    let mut deserializer = bincode::Deserializer::with_reader(&mut reader, options);
+    let output = match schema {
+        Some(schema) => schema::decode_with_schema(&mut deserializer, schema)
+            .map_err(|err| VerifyError::OutputDecode(err.to_string()))?,
+        None => to_json_output(
+            HyleOutput::<()>::deserialize(&mut deserializer)
+                .map_err(|err| VerifyError::OutputDecode(err.to_string()))?,
+        )?,
+    };
+    drop(deserializer);
+    if schema.is_some() && !reader.is_empty() {
+        return Err(VerifyError::OutputDecode(format!(
+            "{} bytes of public values left after the program outputs",
+            reader.len()
+        )));
+    }
+    Ok((output, reader.len()))
+}
+
+pub struct Sp1Verifier;
+
+impl Verifier for Sp1Verifier {
+    fn name(&self) -> &'static str {
+        "sp1"
+    }
+
+    /// `program_id` is the verification key as [read_vk] reads it, `proof` a proof of any mode
+    /// as saved by its `save` method. The verification key must hash to `extra_inputs.vk_hash`
+    /// when it is given, and the mode of the proof be one of `extra_inputs.accept_modes`.
+    /// The program outputs are decoded when `extra_inputs` has an `outputs_schema`.
+    fn verify(
+        &self,
+        program_id: &str,
+        proof: &[u8],
+        extra_inputs: &serde_json::Value,
+    ) -> Result<HyleOutput<serde_json::Value>, VerifyError> {
+        let vk = read_vk(program_id)?;
+        if let Some(expected) = extra_input_str(extra_inputs, "vk_hash")? {
+            check_vk_hash(&vk, expected)?;
+        }
+        let accept_modes = match extra_inputs.get("accept_modes") {
+            Some(modes) => serde_json::from_value::<Vec<String>>(modes.clone())
+                .map_err(|err| err.to_string())
+                .and_then(|modes| modes.iter().map(|mode| mode.parse()).collect())
+                .map_err(|err| {
+                    VerifyError::MalformedProof(format!("Invalid accept_modes: {}", err))
+                })?,
+            None => vec![],
+        };
+        let schema = match extra_input_str(extra_inputs, "outputs_schema")? {
+            Some(schema) => Some(schema.parse::<Schema>().map_err(|err| {
+                VerifyError::MalformedProof(format!("Invalid outputs schema: {}", err))
+            })?),
+            None => None,
+        };
+        Ok(verify_proof(&vk, proof, &accept_modes, schema.as_ref())?.output)
+    }
+}
+
+pub fn decode_vk(b64_vk: &str) -> Result<SP1VerifyingKey, VerifyError> {
+    let vk_json = BASE64_STANDARD
+        .decode(b64_vk)
+        .ok()
+        .and_then(|vk| String::from_utf8(vk).ok())
+        .ok_or_else(|| VerifyError::WrongProgramId("vk is not base64 encoded JSON".to_string()))?;
+    parse_vk_json(&vk_json)
+}
+
+fn parse_vk_json(vk_json: &str) -> Result<SP1VerifyingKey, VerifyError> {
+    Ok(SP1VerifyingKey {
+        vk: serde_json::from_str(vk_json)
+            .map_err(|err| VerifyError::WrongProgramId(err.to_string()))?,
+    })
+}
+
+/// Reads a verification key given as base64 encoded JSON, or as the path of a file holding
+/// its JSON or base64 encoded JSON.
+pub fn read_vk(vk: &str) -> Result<SP1VerifyingKey, VerifyError> {
+    if !Path::new(vk).is_file() {
+        return decode_vk(vk);
+    }
+    let content = String::from_utf8(hyle_verifier_core::read_file(vk)?).map_err(|_| {
+        VerifyError::WrongProgramId(format!("{} is not a JSON verification key", vk))
+    })?;
+    match content.trim_start().starts_with('{') {
+        true => parse_vk_json(&content),
+        false => decode_vk(content.trim()),
+    }
+}
+
+/// Hash of the verification key to register a program by: the lowercase hex encoded SHA-256
+/// of `bincode::serialize(&vk.vk)`, with bincode 1 default options (little endian, fixed size
+/// integers and u64 length prefixes). It is not the vkey hash SP1 computes for its on-chain
+/// verifiers.
+pub fn vk_hash(vk: &SP1VerifyingKey) -> Result<String, VerifyError> {
+    let vk = bincode::serialize(&vk.vk).map_err(|err| VerifyError::Internal(err.to_string()))?;
+    Ok(Sha256::digest(vk)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Checks the verification key hashes to the expected hex encoded hash.
+pub fn check_vk_hash(vk: &SP1VerifyingKey, expected: &str) -> Result<(), VerifyError> {
+    let hash = vk_hash(vk)?;
+    let expected = expected.trim().trim_start_matches("0x").to_lowercase();
+    if hash != expected {
+        return Err(VerifyError::WrongProgramId(format!(
+            "the verification key hashes to {}, expected {}",
+            hash, expected
+        )));
+    }
+    Ok(())
+}
+
+/// Computes the verification key of a program from its ELF.
+pub fn vk_from_elf(elf: &[u8]) -> SP1VerifyingKey {
+    let (_, vk) = prover_client().setup(elf);
+    vk
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use hyle_contract::HyleOutput;
+    use hyle_verifier_core::{Verifier, VerifyError};
+
+    use super::{
+        check_vk_hash, decode_public_values, decode_vk, verify_proof, vk_hash, ProofMode,
+        Sp1Verifier,
+    };
+
+    fn fixture(name: &str) -> Vec<u8> {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("example")
+            .join(name);
+        std::fs::read(&path)
+            .unwrap_or_else(|err| panic!("Failed to read {}: {}", path.display(), err))
+    }
+
+    #[test]
+    fn test_reject_malformed_inputs() {
+        let err = Sp1Verifier
+            .verify("not base64", &[], &serde_json::Value::Null)
+            .unwrap_err();
+        assert!(matches!(err, VerifyError::WrongProgramId(_)));
+
+        let vk = base64::Engine::encode(&base64::prelude::BASE64_STANDARD, "{}");
+        let err = Sp1Verifier
+            .verify(&vk, &[1, 2, 3], &serde_json::Value::Null)
+            .unwrap_err();
+        assert!(matches!(err, VerifyError::WrongProgramId(_)));
+    }
+
+    #[test]
+    fn test_decode_public_values() {
+        let output = HyleOutput {
+            version: 1,
+            initial_state: vec![1, 2],
+            next_state: vec![3],
+            origin: "alice".to_string(),
+            caller: "bob".to_string(),
+            block_number: 4,
+            block_time: 5,
+            tx_hash: vec![6],
+            program_outputs: ("carol".to_string(), 7u64),
+        };
+        // As committed by sp1_zkvm::io::commit
+        let public_values = bincode::serialize(&output).unwrap();
+
+        let (decoded, trailing_bytes) = decode_public_values(&public_values, None).unwrap();
+        assert_eq!(decoded.origin, "alice");
+        assert_eq!(decoded.program_outputs, serde_json::Value::Null);
+        assert_eq!(trailing_bytes, 8 + 5 + 8);
+
+        let schema = "to:string,amount:u64".parse().unwrap();
+        let (decoded, trailing_bytes) =
+            decode_public_values(&public_values, Some(&schema)).unwrap();
+        assert_eq!(
+            decoded.program_outputs,
+            serde_json::json!({ "to": "carol", "amount": 7 })
+        );
+        assert_eq!(trailing_bytes, 0);
+
+        // Bytes the schema does not account for
+        let schema = "to:string".parse().unwrap();
+        let err = decode_public_values(&public_values, Some(&schema)).unwrap_err();
+        assert!(matches!(err, VerifyError::OutputDecode(_)));
+
+        // Truncated public values
+        let err = decode_public_values(&public_values[..20], None).unwrap_err();
+        assert!(matches!(err, VerifyError::OutputDecode(_)));
+
+        // A length prefix far longer than the public values
+        let mut crafted = public_values.clone();
+        crafted[4..12].copy_from_slice(&u64::MAX.to_le_bytes());
+        let err = decode_public_values(&crafted, None).unwrap_err();
+        assert!(matches!(err, VerifyError::OutputDecode(_)));
+    }
+
+    #[test]
+    #[ignore = "needs example/program.proof and example/program.vk from an SP1 prover"]
+    fn test_verify_fixture() {
+        let vk = String::from_utf8(fixture("program.vk")).unwrap();
+        let proof = fixture("program.proof");
+        let output = Sp1Verifier
+            .verify(vk.trim(), &proof, &serde_json::Value::Null)
+            .unwrap();
+        assert_eq!(output.version, 1);
+
+        // A tampered proof fails
+        let mut tampered = proof.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(Sp1Verifier
+            .verify(vk.trim(), &tampered, &serde_json::Value::Null)
+            .is_err());
+    }
+
+    #[test]
+    #[ignore = "needs example/program.proof and example/program.vk from an SP1 prover"]
+    fn test_check_vk_hash() {
+        let vk = String::from_utf8(fixture("program.vk")).unwrap();
+        let hash = vk_hash(&decode_vk(vk.trim()).unwrap()).unwrap();
+        check_vk_hash(
+            &decode_vk(vk.trim()).unwrap(),
+            &format!("0x{}", hash.to_uppercase()),
+        )
+        .unwrap();
+
+        let proof = fixture("program.proof");
+        let extra_inputs = serde_json::json!({ "vk_hash": "00".repeat(32) });
+        let err = Sp1Verifier
+            .verify(vk.trim(), &proof, &extra_inputs)
+            .unwrap_err();
+        assert!(matches!(err, VerifyError::WrongProgramId(_)));
+        let extra_inputs = serde_json::json!({ "vk_hash": hash });
+        assert!(Sp1Verifier.verify(vk.trim(), &proof, &extra_inputs).is_ok());
+    }
+
+    #[test]
+    #[ignore = "needs example/program.proof and example/program.vk from an SP1 prover"]
+    fn test_accept_modes() {
+        let vk = decode_vk(String::from_utf8(fixture("program.vk")).unwrap().trim()).unwrap();
+        let proof = fixture("program.proof");
+        let output = verify_proof(&vk, &proof, &[], None).unwrap();
+        assert_eq!(output.proof_mode, ProofMode::Core);
+
+        let err = verify_proof(&vk, &proof, &[ProofMode::Groth16], None).unwrap_err();
+        assert!(matches!(err, VerifyError::VerificationFailed(_)));
+    }
+}
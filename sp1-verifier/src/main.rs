@@ -1,31 +1,61 @@
-use std::env;
-
-use base64::prelude::*;
-
-use sp1_sdk::{ProverClient, SP1Proof, SP1VerifyingKey};
-
-use hyle_contract::HyleOutput;
+use clap::Parser;
+use hyle_verifier_core::schema::Schema;
+use hyle_verifier_core::{read_file, VerifyError};
+use sp1_verifier::{
+    check_vk_hash, read_vk, verify_proof, vk_from_elf, vk_hash, ProofMode, Sp1Output,
+};
+
+#[derive(Parser, Debug)]
+#[clap(about = "Verify a SP1 proof and print its HyleOutput")]
+struct Cli {
+    /// Verification key, base64 encoded JSON or the path of a file holding it
+    vk: String,
+    /// Proof file of any mode, as saved by its save method
+    proof_path: String,
+    /// Refuse the proof unless the verification key has this hash, as printed in vk_hash
+    #[clap(long)]
+    vk_hash: Option<String>,
+    /// Refuse the proof unless the verification key is the one of this program ELF
+    #[clap(long)]
+    elf: Option<String>,
+    /// Only accept proofs of these modes: core, compressed, plonk or groth16.
+    /// All modes are accepted by default.
+    #[clap(long, value_delimiter = ',')]
+    accept_modes: Vec<ProofMode>,
+    /// Decode the program outputs as these fields, e.g. "from:string,to:string,amount:u64".
+    /// Types are bool, u8, u16, u32, u64, i8, i16, i32, i64, string and bytes.
+    #[clap(long)]
+    outputs_schema: Option<Schema>,
+}
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() != 3 {
-        eprintln!("Usage: {} <b64_encoded_verification_key> <receipt_path>", args[0]);
-        std::process::exit(1);
+    let args = Cli::parse();
+
+    match run(&args) {
+        Ok(output) => {
+            // Outputs to stdout for the caller to read.
+            println!(
+                "{}",
+                serde_json::to_string(&output).expect("Failed to serialize output")
+            );
+        }
+        Err(err) => err.exit(),
     }
+}
 
-    let vk_json = String::from_utf8(BASE64_STANDARD.decode(&args[1]).expect("vk decoding failed")).expect("Fail to cast vk to json string");
-
-    let vk: SP1VerifyingKey = SP1VerifyingKey{
-        vk: serde_json::from_str(&vk_json).unwrap()
-    };
-    let mut proof = SP1Proof::load(&args[2]).expect("loading proof failed");
-
-    // TODO: check that vk is correct ?
-    let prover_client = ProverClient::new();
-    prover_client.verify(&proof, &vk).expect("verification failed");
-
-    // Outputs to stdout for the caller to read.
-    let output: HyleOutput<()> = proof.public_values.read::<HyleOutput<()>>();
-    println!("{}", serde_json::to_string(&output).expect("Failed to serialize output"));
-
+fn run(args: &Cli) -> Result<Sp1Output<serde_json::Value>, VerifyError> {
+    let vk = read_vk(&args.vk)?;
+    if let Some(expected) = &args.vk_hash {
+        check_vk_hash(&vk, expected)?;
+    }
+    if let Some(elf) = &args.elf {
+        check_vk_hash(&vk, &vk_hash(&vk_from_elf(&read_file(elf)?))?)?;
+    }
+    let proof = read_file(&args.proof_path)?;
+    verify_proof(
+        &vk,
+        &proof,
+        &args.accept_modes,
+        args.outputs_schema.as_ref(),
+    )
 }
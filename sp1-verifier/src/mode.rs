@@ -0,0 +1,202 @@
+use std::fmt;
+use std::str::FromStr;
+
+use bincode::Options;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sp1_sdk::{
+    ProverClient, SP1CompressedProof, SP1Groth16Proof, SP1PlonkBn254Proof, SP1Proof,
+    SP1PublicValues, SP1VerifyingKey,
+};
+
+use hyle_verifier_core::VerifyError;
+
+/// Modes of SP1 proofs, from the largest and cheapest to prove to the smallest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofMode {
+    /// One STARK proof per shard of the execution.
+    Core,
+    /// A single STARK proof, recursively compressed from the shards.
+    Compressed,
+    /// A PLONK SNARK wrapping a compressed proof.
+    Plonk,
+    /// A Groth16 SNARK wrapping a compressed proof.
+    Groth16,
+}
+
+impl ProofMode {
+    pub const ALL: [ProofMode; 4] = [
+        ProofMode::Core,
+        ProofMode::Compressed,
+        ProofMode::Plonk,
+        ProofMode::Groth16,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProofMode::Core => "core",
+            ProofMode::Compressed => "compressed",
+            ProofMode::Plonk => "plonk",
+            ProofMode::Groth16 => "groth16",
+        }
+    }
+}
+
+impl fmt::Display for ProofMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ProofMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ProofMode::ALL
+            .into_iter()
+            .find(|mode| mode.as_str() == s)
+            .ok_or_else(|| {
+                let modes: Vec<&str> = ProofMode::ALL.iter().map(ProofMode::as_str).collect();
+                format!(
+                    "unknown proof mode '{}', expected one of {}",
+                    s,
+                    modes.join(", ")
+                )
+            })
+    }
+}
+
+/// Checks the mode is one of the accepted ones, any mode is accepted when none are given.
+pub fn check_mode(mode: ProofMode, accepted: &[ProofMode]) -> Result<(), String> {
+    if accepted.is_empty() || accepted.contains(&mode) {
+        return Ok(());
+    }
+    let accepted: Vec<&str> = accepted.iter().map(ProofMode::as_str).collect();
+    Err(format!(
+        "{} proofs are not accepted, expected {}",
+        mode,
+        accepted.join(" or ")
+    ))
+}
+
+/// A proof of any mode, as saved by its `save` method.
+pub enum AnyProof {
+    Core(SP1Proof),
+    Compressed(SP1CompressedProof),
+    Plonk(SP1PlonkBn254Proof),
+    Groth16(SP1Groth16Proof),
+}
+
+impl AnyProof {
+    fn parse(proof: &[u8], mode: ProofMode) -> Option<AnyProof> {
+        match mode {
+            ProofMode::Core => strict(proof).map(AnyProof::Core),
+            ProofMode::Compressed => strict(proof).map(AnyProof::Compressed),
+            ProofMode::Plonk => strict(proof).map(AnyProof::Plonk),
+            ProofMode::Groth16 => strict(proof).map(AnyProof::Groth16),
+        }
+    }
+
+    pub fn mode(&self) -> ProofMode {
+        match self {
+            AnyProof::Core(_) => ProofMode::Core,
+            AnyProof::Compressed(_) => ProofMode::Compressed,
+            AnyProof::Plonk(_) => ProofMode::Plonk,
+            AnyProof::Groth16(_) => ProofMode::Groth16,
+        }
+    }
+
+    pub fn verify(&self, client: &ProverClient, vk: &SP1VerifyingKey) -> Result<(), VerifyError> {
+        let res = match self {
+            AnyProof::Core(proof) => client.verify(proof, vk).map_err(|err| err.to_string()),
+            AnyProof::Compressed(proof) => client
+                .verify_compressed(proof, vk)
+                .map_err(|err| err.to_string()),
+            AnyProof::Plonk(proof) => client
+                .verify_plonk(proof, vk)
+                .map_err(|err| err.to_string()),
+            AnyProof::Groth16(proof) => client
+                .verify_groth16(proof, vk)
+                .map_err(|err| err.to_string()),
+        };
+        res.map_err(|err| {
+            VerifyError::VerificationFailed(format!("{} proof: {}", self.mode(), err))
+        })
+    }
+
+    pub fn public_values(&self) -> &SP1PublicValues {
+        match self {
+            AnyProof::Core(proof) => &proof.public_values,
+            AnyProof::Compressed(proof) => &proof.public_values,
+            AnyProof::Plonk(proof) => &proof.public_values,
+            AnyProof::Groth16(proof) => &proof.public_values,
+        }
+    }
+}
+
+/// Bincode deserialization, as done by `save`, rejecting trailing bytes so that a proof
+/// only parses as the mode it was saved as.
+fn strict<T: DeserializeOwned>(proof: &[u8]) -> Option<T> {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .deserialize(proof)
+        .ok()
+}
+
+/// Parses the proof as each accepted mode (all when none are given) it can be.
+/// PLONK and Groth16 proofs have the same layout, so both are returned for those: the
+/// one that verifies is the mode of the proof.
+pub fn parse_proof(proof: &[u8], accepted: &[ProofMode]) -> Result<Vec<AnyProof>, VerifyError> {
+    let candidates: Vec<AnyProof> = ProofMode::ALL
+        .into_iter()
+        .filter_map(|mode| AnyProof::parse(proof, mode))
+        .collect();
+    if candidates.is_empty() {
+        return Err(VerifyError::MalformedProof(
+            "not a core, compressed, plonk or groth16 SP1 proof".to_string(),
+        ));
+    }
+    let mut rejected = None;
+    let accepted: Vec<AnyProof> = candidates
+        .into_iter()
+        .filter(|proof| match check_mode(proof.mode(), accepted) {
+            Ok(()) => true,
+            Err(err) => {
+                rejected.get_or_insert(err);
+                false
+            }
+        })
+        .collect();
+    match (accepted.is_empty(), rejected) {
+        (true, Some(err)) => Err(VerifyError::VerificationFailed(err)),
+        _ => Ok(accepted),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_mode, ProofMode};
+
+    #[test]
+    fn test_parse_mode() {
+        for mode in ProofMode::ALL {
+            assert_eq!(mode.as_str().parse::<ProofMode>(), Ok(mode));
+        }
+        assert!("snark".parse::<ProofMode>().is_err());
+    }
+
+    #[test]
+    fn test_check_mode() {
+        assert!(check_mode(ProofMode::Core, &[]).is_ok());
+        assert!(check_mode(ProofMode::Plonk, &[ProofMode::Plonk, ProofMode::Groth16]).is_ok());
+        let err = check_mode(
+            ProofMode::Core,
+            &[ProofMode::Compressed, ProofMode::Groth16],
+        );
+        assert_eq!(
+            err,
+            Err("core proofs are not accepted, expected compressed or groth16".to_string())
+        );
+    }
+}